@@ -11,13 +11,18 @@ use blueprint_sdk::tangle::consumer::TangleConsumer;
 use blueprint_sdk::tangle::filters::MatchesServiceId;
 use blueprint_sdk::tangle::layers::TangleLayer;
 use blueprint_sdk::tangle::producer::TangleProducer;
+use reth_docker_template_blueprint_lib::backup::{self, BackupSchedule};
 use reth_docker_template_blueprint_lib::{
-    RETH_START_JOB_ID, RETH_STOP_JOB_ID, RethConfig, RethContext, reth_start, reth_stop,
+    RETH_GAS_PRICE_JOB_ID, RETH_HEAD_INFO_JOB_ID, RETH_MANAGE_PEERS_JOB_ID,
+    RETH_RPC_PASSTHROUGH_JOB_ID, RETH_STACK_STATUS_JOB_ID, RETH_START_JOB_ID, RETH_STOP_JOB_ID,
+    RETH_TXPOOL_STATUS_JOB_ID, RethConfig, RethContext, reth_gas_price, reth_head_info,
+    reth_manage_peers, reth_rpc_passthrough, reth_stack_status, reth_start, reth_stop,
+    reth_txpool_status,
 };
 use std::path::PathBuf;
 use tower::filter::FilterLayer;
 use tracing::level_filters::LevelFilter;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), blueprint_sdk::Error> {
@@ -35,15 +40,47 @@ async fn main() -> Result<(), blueprint_sdk::Error> {
 
     let tangle_config = TangleConfig::default();
 
-    // Create Reth context with proper configuration
+    // Create Reth context with proper configuration, the optional settings come from `RETH_*`
+    // environment variables
+    let env_config = match RethConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let reth_config = RethConfig {
         // Ensure we're using the correct path to the submodule
         submodule_path: PathBuf::from("local_reth"),
-        block_tip: std::env::var("RETH_TIP").ok(),
         monitoring_port: 9000,
         grafana_port: 3000,
+        rpc_port: 8545,
+        ..env_config
     };
+    // `reth_start` refuses an invalid configuration, so point it out early
+    if let Err(e) = reth_config.validate() {
+        warn!("{}", e);
+    }
     let reth_context = RethContext::new(reth_config.clone());
+    let shutdown_context = reth_context.clone();
+
+    // Optionally take backups on a schedule, e.g. RETH_BACKUP_DIR=/backups for a daily backup
+    let backup_schedule = match BackupSchedule::from_env() {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Some(schedule) = backup_schedule {
+        info!(
+            "Backing up to {} every {}h, keeping the last {}",
+            schedule.dir.display(),
+            schedule.interval.as_secs() / (60 * 60),
+            schedule.retain
+        );
+        tokio::spawn(backup::run_backup_schedule(reth_context.clone(), schedule));
+    }
 
     // Log service URLs
     info!("Service URLs when Reth node is running:");
@@ -56,6 +93,7 @@ async fn main() -> Result<(), blueprint_sdk::Error> {
         "Metrics endpoint: http://localhost:{}",
         reth_config.monitoring_port
     );
+    info!("RPC endpoint: http://localhost:{}", reth_config.rpc_port);
     info!("");
     info!("Available job functions:");
     info!(
@@ -66,14 +104,55 @@ async fn main() -> Result<(), blueprint_sdk::Error> {
         "RETH_STOP_JOB_ID: {} - Stop the Reth node",
         RETH_STOP_JOB_ID
     );
+    info!(
+        "RETH_TXPOOL_STATUS_JOB_ID: {} - Query the transaction pool status",
+        RETH_TXPOOL_STATUS_JOB_ID
+    );
+    info!(
+        "RETH_HEAD_INFO_JOB_ID: {} - Query the latest and finalized block heads",
+        RETH_HEAD_INFO_JOB_ID
+    );
+    info!(
+        "RETH_RPC_PASSTHROUGH_JOB_ID: {} - Forward an allowlisted JSON-RPC call",
+        RETH_RPC_PASSTHROUGH_JOB_ID
+    );
+    info!(
+        "RETH_MANAGE_PEERS_JOB_ID: {} - Add or remove a peer by enode",
+        RETH_MANAGE_PEERS_JOB_ID
+    );
+    info!(
+        "RETH_STACK_STATUS_JOB_ID: {} - Query service health, sync progress and client version",
+        RETH_STACK_STATUS_JOB_ID
+    );
+    info!(
+        "RETH_GAS_PRICE_JOB_ID: {} - Query the gas price and fee suggestions",
+        RETH_GAS_PRICE_JOB_ID
+    );
 
     let service_id = env.protocol_settings.tangle()?.service_id.unwrap();
     let result = BlueprintRunner::builder(tangle_config, env)
         .router(
             Router::new()
-                // Add routes for state-changing operations only
                 .route(RETH_START_JOB_ID, reth_start.layer(TangleLayer))
                 .route(RETH_STOP_JOB_ID, reth_stop.layer(TangleLayer))
+                .route(
+                    RETH_TXPOOL_STATUS_JOB_ID,
+                    reth_txpool_status.layer(TangleLayer),
+                )
+                .route(RETH_HEAD_INFO_JOB_ID, reth_head_info.layer(TangleLayer))
+                .route(
+                    RETH_RPC_PASSTHROUGH_JOB_ID,
+                    reth_rpc_passthrough.layer(TangleLayer),
+                )
+                .route(
+                    RETH_MANAGE_PEERS_JOB_ID,
+                    reth_manage_peers.layer(TangleLayer),
+                )
+                .route(
+                    RETH_STACK_STATUS_JOB_ID,
+                    reth_stack_status.layer(TangleLayer),
+                )
+                .route(RETH_GAS_PRICE_JOB_ID, reth_gas_price.layer(TangleLayer))
                 // Add the service ID filter layer
                 .layer(FilterLayer::new(MatchesServiceId(service_id)))
                 // Set the Reth context
@@ -81,19 +160,15 @@ async fn main() -> Result<(), blueprint_sdk::Error> {
         )
         .producer(tangle_producer)
         .consumer(tangle_consumer)
-        .with_shutdown_handler(async {
+        .with_shutdown_handler(async move {
             info!("Shutting down Reth blueprint!");
             // Try to stop the Reth node on shutdown if it's running
-            let context = RethContext::with_default_config();
+            let context = shutdown_context;
             let status = reth_docker_template_blueprint_lib::monitoring::get_status(&context);
             if let Ok(status_str) = status {
                 if !status_str.contains("No Reth services") {
                     info!("Attempting to stop Reth node...");
-                    let _ = reth_docker_template_blueprint_lib::run_command(
-                        &context,
-                        "docker-compose",
-                        &["down"],
-                    );
+                    let _ = reth_docker_template_blueprint_lib::run_compose(&context, &["down"]);
                 }
             }
         })
@@ -114,9 +189,10 @@ pub fn setup_log() {
         .without_time()
         .with_span_events(tracing_subscriber::fmt::format::FmtSpan::NONE)
         .with_env_filter(
-            tracing_subscriber::EnvFilter::builder()
-                .with_default_directive(LevelFilter::INFO.into())
-                .from_env_lossy(),
+            // Only fall back to INFO when `RUST_LOG` is unset or invalid
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                tracing_subscriber::EnvFilter::default().add_directive(LevelFilter::INFO.into())
+            }),
         )
         .finish()
         .try_init();
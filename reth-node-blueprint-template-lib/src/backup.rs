@@ -0,0 +1,383 @@
+use crate::{Error, Result, RethContext, env_var, parse_env, run_command, run_compose};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
+
+/// Image used for the short-lived containers that read and write the data volume
+pub(crate) const HELPER_IMAGE: &str = "alpine:latest";
+
+/// Name of the docker-compose volume holding the Reth datadir
+const DATA_VOLUME: &str = "rethdata";
+
+/// File name prefix and extension of backup tarballs, around the creation timestamp
+const BACKUP_PREFIX: &str = "reth-backup-";
+const BACKUP_EXTENSION: &str = ".tar.gz";
+
+/// Settings for automatic backups
+#[derive(Debug, Clone)]
+pub struct BackupSchedule {
+    /// Directory the backups are written to
+    pub dir: PathBuf,
+    /// Time between backups
+    pub interval: Duration,
+    /// Number of most recent backups to keep
+    pub retain: usize,
+}
+
+impl BackupSchedule {
+    /// Longest accepted `RETH_BACKUP_INTERVAL_HOURS`, a year
+    const MAX_INTERVAL_HOURS: u64 = 365 * 24;
+
+    /// Read the schedule from `RETH_BACKUP_DIR`, `RETH_BACKUP_INTERVAL_HOURS` (default 24) and
+    /// `RETH_BACKUP_RETAIN` (default 7). Returns `None` if `RETH_BACKUP_DIR` is unset, and
+    /// reports invalid values all at once, like [`crate::RethConfig::from_env`]
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(dir) = env_var("RETH_BACKUP_DIR") else {
+            return Ok(None);
+        };
+
+        let mut problems = Vec::new();
+        let interval_hours = parse_env("RETH_BACKUP_INTERVAL_HOURS", &mut problems).unwrap_or(24);
+        if !(1..=Self::MAX_INTERVAL_HOURS).contains(&interval_hours) {
+            problems.push(format!(
+                "RETH_BACKUP_INTERVAL_HOURS must be between 1 and {}",
+                Self::MAX_INTERVAL_HOURS
+            ));
+        }
+        // Keeping zero backups would delete each one right after it's taken
+        let retain = parse_env("RETH_BACKUP_RETAIN", &mut problems).unwrap_or(7);
+        if retain == 0 {
+            problems.push("RETH_BACKUP_RETAIN must be at least 1".to_string());
+        }
+
+        if !problems.is_empty() {
+            return Err(Error::InvalidConfig(problems));
+        }
+
+        Ok(Some(Self {
+            dir: PathBuf::from(dir),
+            interval: Duration::from_secs(interval_hours * 60 * 60),
+            retain,
+        }))
+    }
+}
+
+/// Get the docker-compose project name, as used to prefix volume names
+pub fn project_name(context: &RethContext) -> Result<String> {
+    if let Some(project_name) = &context.config.project_name {
+        return Ok(project_name.clone());
+    }
+
+    let output = run_compose(context, &["config", "--format", "json"])
+        .map_err(Error::io("Failed to read docker-compose config"))?;
+    let config: Value = serde_json::from_str(&output)
+        .map_err(Error::parse("Failed to parse docker-compose config"))?;
+
+    config["name"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| Error::Other("docker-compose config has no project name".to_string()))
+}
+
+/// Get the Docker volume name of the Reth datadir
+pub fn data_volume_name(context: &RethContext) -> Result<String> {
+    Ok(format!("{}_{}", project_name(context)?, DATA_VOLUME))
+}
+
+/// Check whether a Docker volume exists, without creating it as `docker run -v` would
+pub(crate) fn volume_exists(context: &RethContext, volume: &str) -> Result<bool> {
+    match run_command(context, "docker", &["volume", "inspect", volume]) {
+        Ok(_) => Ok(true),
+        // Docker reports e.g. "Error response from daemon: get <volume>: no such volume"
+        Err(e) if e.to_string().to_lowercase().contains("no such volume") => Ok(false),
+        Err(e) => Err(Error::io(format!("Failed to inspect volume {}", volume))(e)),
+    }
+}
+
+/// Stop the Reth node, archive its datadir volume into `dir`, and restart it if it was running
+///
+/// Returns the path of the created tarball.
+pub fn backup(context: &RethContext, dir: &Path) -> Result<PathBuf> {
+    println!("\n--- Backing up Reth datadir ---");
+
+    std::fs::create_dir_all(dir).map_err(Error::io(format!(
+        "Failed to create backup directory {}",
+        dir.display()
+    )))?;
+    let dir = std::fs::canonicalize(dir).map_err(Error::io(format!(
+        "Invalid backup directory {}",
+        dir.display()
+    )))?;
+
+    let volume = data_volume_name(context)?;
+    if !volume_exists(context, &volume)? {
+        return Err(Error::Other(format!(
+            "Datadir volume {} does not exist, check the compose project name",
+            volume
+        )));
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let file_name = format!("{}{}{}", BACKUP_PREFIX, timestamp, BACKUP_EXTENSION);
+
+    let was_running = crate::monitoring::is_running(context);
+    if was_running {
+        info!(volume = %volume, "Stopping Reth node for backup");
+        run_compose(context, &["stop", "reth"]).map_err(Error::io("Failed to stop Reth node"))?;
+    }
+
+    let data_mount = format!("{}:/data:ro", volume);
+    let backup_mount = format!("{}:/backup", dir.display());
+    let archive = format!("/backup/{}", file_name);
+    let result = run_command(context, "docker", &[
+        "run",
+        "--rm",
+        "-v",
+        &data_mount,
+        "-v",
+        &backup_mount,
+        HELPER_IMAGE,
+        "tar",
+        "czf",
+        &archive,
+        "-C",
+        "/data",
+        ".",
+    ]);
+
+    // Bring the node back up even if archiving failed, but leave a stopped node stopped
+    if was_running {
+        info!("Restarting Reth node");
+        if let Err(e) = run_compose(context, &["start", "reth"]) {
+            warn!(error = %e, "Failed to restart Reth node after backup");
+        }
+    }
+
+    result.map_err(Error::io(format!("Failed to archive {}", volume)))?;
+
+    Ok(dir.join(file_name))
+}
+
+/// Replace the Reth datadir volume with a fresh one populated from a backup tarball
+///
+/// The node is left stopped, start it again once the restore completes.
+pub fn restore(context: &RethContext, tarball: &Path) -> Result<()> {
+    println!("\n--- Restoring Reth datadir ---");
+
+    let tarball = std::fs::canonicalize(tarball).map_err(Error::io(format!(
+        "Invalid backup file {}",
+        tarball.display()
+    )))?;
+    let (Some(dir), Some(file_name)) = (tarball.parent(), tarball.file_name()) else {
+        return Err(Error::Other(format!(
+            "Invalid backup file {}",
+            tarball.display()
+        )));
+    };
+
+    let volume = data_volume_name(context)?;
+
+    info!(volume = %volume, "Removing Reth container and datadir volume");
+    run_compose(context, &["rm", "--stop", "--force", "reth"])
+        .map_err(Error::io("Failed to remove Reth container"))?;
+    if volume_exists(context, &volume)? {
+        // Unpacking over a volume that couldn't be removed would mix old and restored data
+        run_command(context, "docker", &["volume", "rm", &volume]).map_err(Error::io(format!(
+            "Failed to remove datadir volume {}",
+            volume
+        )))?;
+    } else {
+        // The volume may not exist yet, e.g. when restoring onto a new host
+        info!(volume = %volume, "No existing datadir volume to remove");
+    }
+
+    // Let docker-compose recreate the volume, so it stays managed by the project
+    run_compose(context, &["up", "--no-start", "reth"])
+        .map_err(Error::io("Failed to recreate Reth container"))?;
+
+    let data_mount = format!("{}:/data", volume);
+    let backup_mount = format!("{}:/backup:ro", dir.display());
+    let archive = format!("/backup/{}", file_name.to_string_lossy());
+    run_command(context, "docker", &[
+        "run",
+        "--rm",
+        "-v",
+        &data_mount,
+        "-v",
+        &backup_mount,
+        HELPER_IMAGE,
+        "tar",
+        "xzf",
+        &archive,
+        "-C",
+        "/data",
+    ])
+    .map_err(Error::io(format!(
+        "Failed to restore {} into {}",
+        tarball.display(),
+        volume
+    )))?;
+
+    Ok(())
+}
+
+/// Delete all but the newest `retain` backups in `dir`, returning the removed files
+///
+/// A `retain` of 0 is treated as 1, so the newest backup is never deleted.
+pub fn prune_backups(dir: &Path, retain: usize) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir).map_err(Error::io(format!(
+        "Failed to read backup directory {}",
+        dir.display()
+    )))?;
+
+    let mut backups: Vec<(u64, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let timestamp = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix(BACKUP_PREFIX)?
+                .strip_suffix(BACKUP_EXTENSION)?
+                .parse()
+                .ok()?;
+            Some((timestamp, entry.path()))
+        })
+        .collect();
+
+    // Newest first, so everything past `retain` is stale
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut removed = Vec::new();
+    for (_, path) in backups.into_iter().skip(retain.max(1)) {
+        std::fs::remove_file(&path).map_err(Error::io(format!(
+            "Failed to remove old backup {}",
+            path.display()
+        )))?;
+        removed.push(path);
+    }
+
+    Ok(removed)
+}
+
+/// Take a backup every `schedule.interval` and prune old ones, until the task is dropped
+///
+/// Each backup briefly stops the node, the same as a manual [`backup`].
+pub async fn run_backup_schedule(context: RethContext, schedule: BackupSchedule) {
+    let mut interval = tokio::time::interval(schedule.interval);
+    // The first tick completes immediately, skip it so no backup is taken on startup
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        // Only back up a node that is running, a stopped one is left alone
+        if !crate::monitoring::is_running(&context) {
+            info!("Reth node is not running, skipping scheduled backup");
+            continue;
+        }
+
+        let task_context = context.clone();
+        let task_schedule = schedule.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let path = backup(&task_context, &task_schedule.dir)?;
+            let removed = prune_backups(&task_schedule.dir, task_schedule.retain)?;
+            Ok::<_, Error>((path, removed))
+        })
+        .await;
+
+        match result {
+            Ok(Ok((path, removed))) => {
+                info!(path = %path.display(), pruned = removed.len(), "Scheduled backup complete")
+            }
+            Ok(Err(e)) => error!(error = %e, "Scheduled backup failed"),
+            Err(e) => error!(error = %e, "Scheduled backup task panicked"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty directory under the system temp dir, unique to this test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("reth-backup-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(dir: &Path, name: &str) {
+        std::fs::write(dir.join(name), b"").unwrap();
+    }
+
+    fn backup_name(timestamp: u64) -> String {
+        format!("{}{}{}", BACKUP_PREFIX, timestamp, BACKUP_EXTENSION)
+    }
+
+    #[test]
+    fn prune_keeps_newest_backups() {
+        let dir = temp_dir("keep-newest");
+        for timestamp in [100, 300, 200, 400] {
+            touch(&dir, &backup_name(timestamp));
+        }
+
+        let mut removed = prune_backups(&dir, 2).unwrap();
+        removed.sort();
+
+        assert_eq!(removed, [
+            dir.join(backup_name(100)),
+            dir.join(backup_name(200))
+        ]);
+        assert!(dir.join(backup_name(300)).exists());
+        assert!(dir.join(backup_name(400)).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn prune_always_keeps_the_latest_backup() {
+        let dir = temp_dir("retain-zero");
+        touch(&dir, &backup_name(100));
+        touch(&dir, &backup_name(200));
+
+        let removed = prune_backups(&dir, 0).unwrap();
+
+        assert_eq!(removed, [dir.join(backup_name(100))]);
+        assert!(dir.join(backup_name(200)).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn prune_ignores_unrelated_files() {
+        let dir = temp_dir("unrelated");
+        touch(&dir, &backup_name(100));
+        touch(&dir, &backup_name(200));
+        touch(&dir, "notes.txt");
+        touch(&dir, "reth-backup-latest.tar.gz");
+        touch(&dir, "reth-backup-50.tar");
+
+        let removed = prune_backups(&dir, 1).unwrap();
+
+        assert_eq!(removed, [dir.join(backup_name(100))]);
+        assert!(dir.join("notes.txt").exists());
+        assert!(dir.join("reth-backup-latest.tar.gz").exists());
+        assert!(dir.join("reth-backup-50.tar").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn prune_fails_on_missing_directory() {
+        let dir = temp_dir("missing");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(prune_backups(&dir, 1), Err(Error::Io { .. })));
+    }
+}
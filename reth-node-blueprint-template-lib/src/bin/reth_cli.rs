@@ -1,7 +1,10 @@
 use clap::{Parser, Subcommand};
-use reth_docker_template_blueprint_lib::{RethConfig, RethContext, monitoring};
+use reth_docker_template_blueprint_lib::{
+    Error, RethConfig, RethContext, backup, monitoring, run_compose,
+};
 use std::path::PathBuf;
 use std::process::ExitCode;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 use tracing::{debug, error, info, warn};
 
@@ -24,10 +27,18 @@ struct Cli {
     #[arg(long, default_value_t = 9000)]
     monitoring_port: u16,
 
+    /// Docker Compose project name, to run isolated stacks side by side
+    #[arg(long)]
+    project: Option<String>,
+
     /// Verbose output
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "quiet")]
     verbose: bool,
 
+    /// Only show warnings and errors
+    #[arg(short, long)]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -37,11 +48,22 @@ enum Commands {
     /// Start the Reth node
     Start,
 
-    /// Stop the Reth node
+    /// Stop the Reth node, keeping its containers and data
     Stop,
 
+    /// Remove all containers and volumes, deleting the synced chain data
+    Clean {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Get the status of the Reth node
-    Status,
+    Status {
+        /// Print the per-service status as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Get logs from the Reth node
     Logs {
@@ -52,6 +74,14 @@ enum Commands {
         /// Follow the logs (stream logs to terminal)
         #[arg(short, long)]
         follow: bool,
+
+        /// Only show logs since a timestamp (e.g. 2024-01-02T13:23:37Z) or relative duration (e.g. 42m)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only show logs before a timestamp (e.g. 2024-01-02T13:23:37Z) or relative duration (e.g. 42m)
+        #[arg(long)]
+        until: Option<String>,
     },
 
     /// Check if Grafana is ready
@@ -62,39 +92,167 @@ enum Commands {
 
     /// Get URLs for all services
     Urls,
+
+    /// List the host ports published by each service
+    Ports,
+
+    /// Stop the node, archive its datadir into a tarball, and restart it if it was running
+    Backup {
+        /// Directory to write the backup tarball to
+        dir: PathBuf,
+    },
+
+    /// Load a backup tarball into a fresh datadir volume
+    Restore {
+        /// Path to a tarball created by `backup`
+        tarball: PathBuf,
+    },
+
+    /// Show a live view of each service's state, resource usage, and sync progress
+    Top {
+        /// Seconds between refreshes, at least 1
+        #[arg(short, long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+
+    /// Show the container configuration recorded by Docker
+    Inspect {
+        /// Name of the docker-compose service to inspect
+        #[arg(default_value = "reth")]
+        service: String,
+    },
+
+    /// Print the command the Reth container is launched with, after variable interpolation
+    EffectiveCommand,
+
+    /// Wait until the Reth node is running, exiting with an error on timeout
+    Wait {
+        /// Also wait for the container healthcheck to pass and the RPC endpoint to respond
+        #[arg(long)]
+        healthy: bool,
+
+        /// Maximum number of seconds to wait
+        #[arg(short, long, default_value_t = 120)]
+        timeout: u64,
+    },
+
+    /// Check the configuration and the docker-compose file without starting anything
+    Validate,
 }
 
-// Setup logging
-fn setup_logging(verbose: bool) {
+// Setup logging, `RUST_LOG` takes precedence over the verbosity flags
+fn setup_logging(verbose: bool, quiet: bool) {
     use tracing_subscriber::EnvFilter;
     use tracing_subscriber::fmt::format::FmtSpan;
 
-    let default_level = if verbose { "debug" } else { "info" };
+    let default_level = if verbose {
+        "debug"
+    } else if quiet {
+        "warn"
+    } else {
+        "info"
+    };
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_level));
 
     let _ = tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::builder()
-                .with_default_directive(default_level.parse().unwrap())
-                .from_env_lossy(),
-        )
+        .with_env_filter(filter)
         .with_span_events(FmtSpan::NONE)
         .try_init();
 }
 
+// Ask the user to confirm a destructive operation on stdin
+fn confirm(warning: &str) -> bool {
+    use std::io::Write;
+
+    print!("{} Continue? [y/N] ", warning);
+    let _ = std::io::stdout().flush();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+// Print one frame of the `top` view
+fn print_top(context: &RethContext) {
+    // Clear the screen and move the cursor to the top-left corner
+    print!("\x1b[2J\x1b[H");
+
+    let statuses = match monitoring::get_service_statuses(context) {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+    let usage = monitoring::get_resource_usage(context).unwrap_or_default();
+
+    println!(
+        "{:<12} {:<10} {:<10} {:>8} {:>24}",
+        "SERVICE", "STATE", "HEALTH", "CPU", "MEMORY"
+    );
+    for status in &statuses {
+        let (cpu, memory) = usage
+            .get(&status.name)
+            .map(|usage| (usage.cpu.as_str(), usage.memory.as_str()))
+            .unwrap_or(("-", "-"));
+        println!(
+            "{:<12} {:<10} {:<10} {:>8} {:>24}",
+            status.name,
+            status.state,
+            status.health.as_deref().unwrap_or("-"),
+            cpu,
+            memory
+        );
+    }
+    if statuses.is_empty() {
+        println!("No Reth services are currently running.");
+    }
+
+    println!();
+    match monitoring::get_sync_status(context) {
+        Ok(sync) => {
+            let format_block =
+                |block: Option<u64>| block.map_or("-".to_string(), |b| b.to_string());
+            println!(
+                "Block: {} / {}  Syncing: {}  Peers: {}",
+                format_block(sync.current_block),
+                format_block(sync.highest_block.or(sync.current_block)),
+                sync.syncing,
+                format_block(sync.peer_count)
+            );
+        }
+        Err(e) => println!("Sync status unavailable: {}", e),
+    }
+    println!("\nPress Ctrl+C to exit");
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
     // Setup logging
-    setup_logging(cli.verbose);
-
-    // Create context with user-provided configuration
-    let mut config = RethConfig::default();
+    setup_logging(cli.verbose, cli.quiet);
+
+    // Create context from the `RETH_*` environment variables, overridden by the flags
+    let mut config = match RethConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
     if let Some(path) = cli.path {
         config.submodule_path = path;
     }
-    if let Some(block_tip) = cli.block_tip.clone() {
+    if let Some(block_tip) = cli.block_tip {
         config.block_tip = Some(block_tip);
     }
+    if let Some(project) = cli.project.clone() {
+        config.project_name = Some(project);
+    }
     config.grafana_port = cli.grafana_port;
     config.monitoring_port = cli.monitoring_port;
 
@@ -107,14 +265,6 @@ fn main() -> ExitCode {
         Commands::Start => {
             println!("\n--- Starting Reth node ---");
 
-            // Set block tip if provided
-            if let Some(block_tip) = cli.block_tip {
-                // Use unsafe block for environment variable setting
-                unsafe {
-                    std::env::set_var("RETH_TIP", block_tip);
-                }
-            }
-
             let result = rt.block_on(async {
                 use blueprint_sdk::extract::Context;
                 use blueprint_sdk::tangle::extract::TangleArg;
@@ -148,7 +298,34 @@ fn main() -> ExitCode {
                 result => println!("{}", result.0),
             }
         }
-        Commands::Status => {
+        Commands::Clean { yes } => {
+            if !yes
+                && !confirm("This removes all Reth containers and volumes, including synced data.")
+            {
+                println!("Aborted.");
+                return ExitCode::FAILURE;
+            }
+
+            if let Err(e) = reth_docker_template_blueprint_lib::clean(&context) {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+            println!("Reth containers and volumes removed.");
+        }
+        Commands::Status { json: true } => match monitoring::get_service_statuses(&context) {
+            Ok(statuses) => match serde_json::to_string_pretty(&statuses) {
+                Ok(output) => println!("{}", output),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            },
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Commands::Status { json: false } => {
             let status = monitoring::get_status(&context);
             match status {
                 Ok(output) => println!("{}", output),
@@ -158,15 +335,29 @@ fn main() -> ExitCode {
                 }
             }
         }
-        Commands::Logs { lines, follow } => {
+        Commands::Logs {
+            lines,
+            follow,
+            since,
+            until,
+        } => {
             if follow {
                 // This will be handled directly by run_command_with_logs in the lib.rs file
                 let result = rt.block_on(async {
                     use blueprint_sdk::extract::Context;
-                    use reth_docker_template_blueprint_lib::run_command_with_logs;
+                    use reth_docker_template_blueprint_lib::run_compose_with_logs;
+
+                    let mut args = vec!["logs", "--follow"];
+                    if let Some(since) = &since {
+                        args.extend(["--since", since.as_str()]);
+                    }
+                    if let Some(until) = &until {
+                        args.extend(["--until", until.as_str()]);
+                    }
+                    args.push("reth");
 
                     println!("\n--- Following Reth node logs (press Ctrl+C to stop) ---");
-                    run_command_with_logs(&context, "docker-compose", &["logs", "--follow", "reth"])
+                    run_compose_with_logs(&context, &args)
                 });
 
                 if let Err(e) = result {
@@ -174,7 +365,8 @@ fn main() -> ExitCode {
                     return ExitCode::FAILURE;
                 }
             } else {
-                let logs = monitoring::get_logs(&context, lines);
+                let logs =
+                    monitoring::get_logs(&context, lines, since.as_deref(), until.as_deref());
                 match logs {
                     Ok(output) => println!("{}", output),
                     Err(e) => {
@@ -205,6 +397,11 @@ fn main() -> ExitCode {
                         }
                     }
                     println!("Retrieved {} metrics", metrics.len());
+                    if metrics.is_empty() {
+                        println!(
+                            "The Reth node is running, but its metrics endpoint is not responding yet."
+                        );
+                    }
                 }
                 Err(e) => {
                     eprintln!("Error: {}", e);
@@ -212,6 +409,32 @@ fn main() -> ExitCode {
                 }
             }
         }
+        Commands::Ports => match monitoring::get_service_statuses(&context) {
+            Ok(statuses) => {
+                if statuses.is_empty() {
+                    println!("No Reth services exist yet, start the node to publish its ports.");
+                    return ExitCode::SUCCESS;
+                }
+                println!("{:<12} {:<24} {:<10}", "SERVICE", "HOST", "CONTAINER");
+                for status in &statuses {
+                    for port in &status.ports {
+                        println!(
+                            "{:<12} {:<24} {:<10}",
+                            status.name,
+                            format!("{}:{}", port.host_ip, port.host_port),
+                            format!("{}/{}", port.container_port, port.protocol)
+                        );
+                    }
+                    if status.ports.is_empty() {
+                        println!("{:<12} {:<24} {:<10}", status.name, "-", "-");
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
         Commands::Urls => {
             let urls = monitoring::get_service_urls(&context);
             println!("Service URLs:");
@@ -219,6 +442,79 @@ fn main() -> ExitCode {
                 println!("  {}: {}", service, url);
             }
         }
+        Commands::Backup { dir } => match backup::backup(&context, &dir) {
+            Ok(path) => println!("Backup written to {}", path.display()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Commands::Restore { tarball } => match backup::restore(&context, &tarball) {
+            Ok(()) => println!("Backup restored. Run 'reth-cli start' to start the node."),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Commands::Top { interval } => loop {
+            print_top(&context);
+            std::thread::sleep(Duration::from_secs(interval));
+        },
+        Commands::Inspect { service } => match monitoring::inspect_service(&context, &service) {
+            Ok(inspect) => match serde_json::to_string_pretty(&inspect) {
+                Ok(output) => println!("{}", output),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            },
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Commands::EffectiveCommand => match monitoring::effective_command(&context) {
+            Ok(command) => println!("{}", command.join(" ")),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Commands::Wait { healthy, timeout } => {
+            println!("\n--- Waiting for Reth node ---");
+            match monitoring::wait_for_node(&context, Duration::from_secs(timeout), healthy) {
+                Ok(()) => println!("Reth node is ready"),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Commands::Validate => {
+            println!("\n--- Validating configuration ---");
+            match context.config.validate() {
+                Ok(()) => {}
+                Err(Error::InvalidConfig(problems)) => {
+                    eprintln!("Found {} configuration problem(s):", problems.len());
+                    for problem in problems {
+                        eprintln!("  - {}", problem);
+                    }
+                    return ExitCode::FAILURE;
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+
+            // Let docker-compose check the file with the configured variables interpolated
+            if let Err(e) = run_compose(&context, &["config", "--quiet"]) {
+                eprintln!("Invalid docker-compose configuration: {}", e);
+                return ExitCode::FAILURE;
+            }
+
+            println!("Configuration is valid");
+        }
     }
 
     ExitCode::SUCCESS
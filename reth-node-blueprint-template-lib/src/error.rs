@@ -0,0 +1,47 @@
+use serde_json::Value;
+
+/// Errors returned by the Reth blueprint library
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An I/O operation failed, including running `docker`, `docker-compose` or `curl`
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// Command or RPC output could not be parsed
+    #[error("{context}: {source}")]
+    Parse {
+        context: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The Reth node answered a JSON-RPC request with an error object
+    #[error("RPC call {method} failed: {error}")]
+    Rpc { method: String, error: Value },
+    /// The configuration has one or more problems, see [`RethConfig::validate`](crate::RethConfig::validate)
+    #[error("Invalid configuration: {}", .0.join("; "))]
+    InvalidConfig(Vec<String>),
+    /// A service required by the operation is not running
+    #[error("{0}")]
+    NotRunning(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl Error {
+    /// Wrap an I/O error with a description of what was being attempted
+    pub(crate) fn io(context: impl Into<String>) -> impl FnOnce(std::io::Error) -> Self {
+        let context = context.into();
+        move |source| Self::Io { context, source }
+    }
+
+    /// Wrap a parse failure with a description of what was being parsed
+    pub(crate) fn parse(context: impl Into<String>) -> impl FnOnce(serde_json::Error) -> Self {
+        let context = context.into();
+        move |source| Self::Parse { context, source }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
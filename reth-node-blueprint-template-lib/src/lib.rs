@@ -1,16 +1,71 @@
 use blueprint_sdk::extract::Context;
 use blueprint_sdk::tangle::extract::{Optional, TangleArg, TangleResult};
+use serde_json::json;
+use std::io::Write;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::string::String;
+use std::time::Duration;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 // Create modules
+pub mod backup;
+mod error;
 pub mod monitoring;
 
-// The job IDs - only for state-changing operations
+pub use error::{Error, Result};
+
+// The job IDs
 pub const RETH_START_JOB_ID: u32 = 1;
 pub const RETH_STOP_JOB_ID: u32 = 2;
+pub const RETH_TXPOOL_STATUS_JOB_ID: u32 = 3;
+pub const RETH_HEAD_INFO_JOB_ID: u32 = 4;
+pub const RETH_RPC_PASSTHROUGH_JOB_ID: u32 = 5;
+pub const RETH_MANAGE_PEERS_JOB_ID: u32 = 6;
+pub const RETH_STACK_STATUS_JOB_ID: u32 = 7;
+pub const RETH_GAS_PRICE_JOB_ID: u32 = 8;
+
+// Read-only RPC methods that `reth_rpc_passthrough` forwards by default
+pub const DEFAULT_RPC_ALLOWLIST: &[&str] = &[
+    "eth_blockNumber",
+    "eth_call",
+    "eth_chainId",
+    "eth_estimateGas",
+    "eth_feeHistory",
+    "eth_gasPrice",
+    "eth_getBalance",
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getCode",
+    "eth_getLogs",
+    "eth_getStorageAt",
+    "eth_getTransactionByHash",
+    "eth_getTransactionCount",
+    "eth_getTransactionReceipt",
+    "eth_syncing",
+    "net_peerCount",
+    "net_version",
+    "txpool_status",
+    "web3_clientVersion",
+];
+
+// HTTP RPC namespaces served by default, and when `admin_api` is enabled
+const HTTP_API: &str = "eth,net,web3,txpool";
+const HTTP_API_WITH_ADMIN: &str = "eth,net,web3,txpool,admin";
+
+// Docker log drivers that accept the `max-size` and `max-file` options set by the compose file
+pub const LOG_DRIVERS: &[&str] = &["json-file", "local"];
+
+// The compose file in the submodule directory, and the override that sets the network MTU
+const COMPOSE_FILE: &str = "docker-compose.yml";
+const MTU_COMPOSE_FILE: &str = "docker-compose.mtu.yml";
+
+// Authentication for the Reth JSON-RPC endpoint, e.g. when it sits behind an auth proxy
+#[derive(Clone)]
+pub enum RpcAuth {
+    Bearer(String),
+    Basic { username: String, password: String },
+}
 
 // Configuration for the Reth node
 #[derive(Clone)]
@@ -19,6 +74,51 @@ pub struct RethConfig {
     pub block_tip: Option<String>,
     pub monitoring_port: u16,
     pub grafana_port: u16,
+    pub rpc_port: u16,
+    pub rpc_auth: Option<RpcAuth>,
+    pub rpc_allowlist: Vec<String>,
+    // Serve the `admin` namespace, needed by `reth_manage_peers`. The RPC listens on all container
+    // interfaces, so this exposes it to every container on the compose network
+    pub admin_api: bool,
+    // Limit for a whole RPC request, so an unresponsive endpoint can't block a job
+    pub rpc_timeout: Duration,
+    pub project_name: Option<String>,
+    pub max_rpc_connections: Option<u32>,
+    // Maximum RPC request and response sizes, in megabytes
+    pub rpc_max_request_size: Option<u32>,
+    pub rpc_max_response_size: Option<u32>,
+    // Maximum gas an `eth_call` or `eth_estimateGas` may use
+    pub rpc_gas_cap: Option<u64>,
+    pub trusted_peers: Vec<String>,
+    pub trusted_only: bool,
+    // Disable all peer discovery and only connect to trusted peers, for isolated networks
+    pub private_network: bool,
+    pub disable_discovery: bool,
+    pub disable_dns_discovery: bool,
+    // UDP discovery port, when it must differ from the TCP listen port
+    pub discovery_port: Option<u16>,
+    // Don't save known peers to the datadir on shutdown
+    pub no_persist_peers: bool,
+    pub gas_limit: Option<u64>,
+    pub txpool_pending_max_count: Option<usize>,
+    pub txpool_queued_max_count: Option<usize>,
+    // Engine tuning for slower disks: how many blocks to keep in memory before persisting
+    pub engine_persistence_threshold: Option<u64>,
+    pub engine_memory_block_buffer_target: Option<u64>,
+    // Docker log driver of the compose services, and its rotation limits (e.g. `100m`, 3 files).
+    // The compose file always passes the limits, so only the `json-file` and `local` drivers,
+    // which support them, are accepted
+    pub log_driver: Option<String>,
+    pub log_max_size: Option<String>,
+    pub log_max_file: Option<u32>,
+    // Docker restart policy of the compose services: `no`, `always`, `unless-stopped` or
+    // `on-failure[:max-retries]`, so a crashing container doesn't restart forever. Defaults to
+    // `unless-stopped`, so a node stopped with `stop` stays stopped across Docker restarts
+    pub restart_policy: Option<String>,
+    // MTU of the compose network, for hosts whose network path is smaller than the default 1500.
+    // Left to Docker (and a daemon-wide `mtu`) when unset. Only applied when the network is
+    // created, so it needs a `docker-compose down` to change
+    pub network_mtu: Option<u32>,
 }
 
 impl Default for RethConfig {
@@ -28,8 +128,339 @@ impl Default for RethConfig {
             block_tip: None,
             monitoring_port: 9000,
             grafana_port: 3000,
+            rpc_port: 8545,
+            rpc_auth: None,
+            rpc_allowlist: DEFAULT_RPC_ALLOWLIST
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            admin_api: false,
+            rpc_timeout: Duration::from_secs(10),
+            project_name: None,
+            max_rpc_connections: None,
+            rpc_max_request_size: None,
+            rpc_max_response_size: None,
+            rpc_gas_cap: None,
+            trusted_peers: Vec::new(),
+            trusted_only: false,
+            private_network: false,
+            disable_discovery: false,
+            disable_dns_discovery: false,
+            discovery_port: None,
+            no_persist_peers: false,
+            gas_limit: None,
+            txpool_pending_max_count: None,
+            txpool_queued_max_count: None,
+            engine_persistence_threshold: None,
+            engine_memory_block_buffer_target: None,
+            log_driver: None,
+            log_max_size: None,
+            log_max_file: None,
+            restart_policy: None,
+            network_mtu: None,
+        }
+    }
+}
+
+// Read a non-empty environment variable
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+// Parse an environment variable, recording a problem if it is set but invalid
+fn parse_env<T: std::str::FromStr>(name: &str, problems: &mut Vec<String>) -> Option<T> {
+    let value = env_var(name)?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            problems.push(format!("{} has an invalid value {:?}", name, value));
+            None
+        }
+    }
+}
+
+// Read a boolean environment variable, accepting `true`/`false`, `1`/`0` and `yes`/`no`
+fn env_flag(name: &str, problems: &mut Vec<String>) -> bool {
+    match env_var(name).as_deref() {
+        None => false,
+        Some("true" | "1" | "yes") => true,
+        Some("false" | "0" | "no") => false,
+        Some(value) => {
+            problems.push(format!("{} has an invalid value {:?}", name, value));
+            false
+        }
+    }
+}
+
+// Read a comma-separated environment variable
+fn env_list(name: &str) -> Option<Vec<String>> {
+    env_var(name).map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+impl RethConfig {
+    // Build a configuration from the defaults and the `RETH_*` environment variables documented in
+    // the README. Invalid values are reported all at once, like `validate`
+    pub fn from_env() -> Result<Self> {
+        let mut problems = Vec::new();
+        let defaults = Self::default();
+
+        let config = Self {
+            block_tip: env_var("RETH_TIP"),
+            project_name: env_var("RETH_COMPOSE_PROJECT"),
+            rpc_auth: match (
+                env_var("RETH_RPC_AUTH_TOKEN"),
+                env_var("RETH_RPC_AUTH_USER"),
+            ) {
+                (Some(token), _) => Some(RpcAuth::Bearer(token)),
+                (None, Some(username)) => Some(RpcAuth::Basic {
+                    username,
+                    password: env_var("RETH_RPC_AUTH_PASSWORD").unwrap_or_default(),
+                }),
+                (None, None) => None,
+            },
+            rpc_allowlist: env_list("RETH_RPC_ALLOWLIST").unwrap_or(defaults.rpc_allowlist),
+            admin_api: env_flag("RETH_ADMIN_API", &mut problems),
+            rpc_timeout: parse_env("RETH_RPC_TIMEOUT_SECS", &mut problems)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.rpc_timeout),
+            max_rpc_connections: parse_env("RETH_RPC_MAX_CONNECTIONS", &mut problems),
+            rpc_max_request_size: parse_env("RETH_RPC_MAX_REQUEST_SIZE", &mut problems),
+            rpc_max_response_size: parse_env("RETH_RPC_MAX_RESPONSE_SIZE", &mut problems),
+            rpc_gas_cap: parse_env("RETH_RPC_GAS_CAP", &mut problems),
+            trusted_peers: env_list("RETH_TRUSTED_PEERS").unwrap_or_default(),
+            trusted_only: env_flag("RETH_TRUSTED_ONLY", &mut problems),
+            private_network: env_flag("RETH_PRIVATE_NETWORK", &mut problems),
+            disable_discovery: env_flag("RETH_DISABLE_DISCOVERY", &mut problems),
+            disable_dns_discovery: env_flag("RETH_DISABLE_DNS_DISCOVERY", &mut problems),
+            discovery_port: parse_env("RETH_DISCOVERY_PORT", &mut problems),
+            no_persist_peers: env_flag("RETH_NO_PERSIST_PEERS", &mut problems),
+            gas_limit: parse_env("RETH_GAS_LIMIT", &mut problems),
+            txpool_pending_max_count: parse_env("RETH_TXPOOL_PENDING_MAX_COUNT", &mut problems),
+            txpool_queued_max_count: parse_env("RETH_TXPOOL_QUEUED_MAX_COUNT", &mut problems),
+            engine_persistence_threshold: parse_env(
+                "RETH_ENGINE_PERSISTENCE_THRESHOLD",
+                &mut problems,
+            ),
+            engine_memory_block_buffer_target: parse_env(
+                "RETH_ENGINE_MEMORY_BLOCK_BUFFER_TARGET",
+                &mut problems,
+            ),
+            log_driver: env_var("RETH_LOG_DRIVER"),
+            log_max_size: env_var("RETH_LOG_MAX_SIZE"),
+            log_max_file: parse_env("RETH_LOG_MAX_FILE", &mut problems),
+            restart_policy: env_var("RETH_RESTART_POLICY"),
+            network_mtu: parse_env("RETH_NETWORK_MTU", &mut problems),
+            ..defaults
+        };
+
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(Error::InvalidConfig(problems))
+        }
+    }
+
+    // Additional Reth CLI flags derived from the optional settings
+    pub fn reth_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(max_connections) = self.max_rpc_connections {
+            args.push("--rpc.max-connections".to_string());
+            args.push(max_connections.to_string());
+        }
+        if let Some(max_size) = self.rpc_max_request_size {
+            args.push("--rpc.max-request-size".to_string());
+            args.push(max_size.to_string());
+        }
+        if let Some(max_size) = self.rpc_max_response_size {
+            args.push("--rpc.max-response-size".to_string());
+            args.push(max_size.to_string());
+        }
+        if let Some(gas_cap) = self.rpc_gas_cap {
+            args.push("--rpc.gascap".to_string());
+            args.push(gas_cap.to_string());
+        }
+        if !self.trusted_peers.is_empty() {
+            args.push("--trusted-peers".to_string());
+            args.push(self.trusted_peers.join(","));
+        }
+        if self.trusted_only || self.private_network {
+            args.push("--trusted-only".to_string());
+        }
+        if self.disable_discovery || self.private_network {
+            args.push("--disable-discovery".to_string());
+        } else if self.disable_dns_discovery {
+            // `--disable-discovery` already covers DNS discovery
+            args.push("--disable-dns-discovery".to_string());
+        }
+        if let Some(port) = self.discovery_port {
+            args.push("--discovery.port".to_string());
+            args.push(port.to_string());
+        }
+        if self.no_persist_peers {
+            args.push("--no-persist-peers".to_string());
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            args.push("--builder.gaslimit".to_string());
+            args.push(gas_limit.to_string());
+        }
+        if let Some(max_count) = self.txpool_pending_max_count {
+            args.push("--txpool.pending-max-count".to_string());
+            args.push(max_count.to_string());
+        }
+        if let Some(max_count) = self.txpool_queued_max_count {
+            args.push("--txpool.queued-max-count".to_string());
+            args.push(max_count.to_string());
+        }
+        if let Some(threshold) = self.engine_persistence_threshold {
+            args.push("--engine.persistence-threshold".to_string());
+            args.push(threshold.to_string());
+        }
+        if let Some(target) = self.engine_memory_block_buffer_target {
+            args.push("--engine.memory-block-buffer-target".to_string());
+            args.push(target.to_string());
+        }
+        args
+    }
+
+    // Check the configuration for problems that would only surface once the node is started,
+    // reporting all of them at once
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if !self.submodule_path.join(COMPOSE_FILE).is_file() {
+            problems.push(format!(
+                "{} does not contain a docker-compose.yml",
+                self.submodule_path.display()
+            ));
+        }
+
+        let ports = [
+            ("monitoring_port", self.monitoring_port),
+            ("grafana_port", self.grafana_port),
+            ("rpc_port", self.rpc_port),
+        ];
+        for (i, (name, port)) in ports.iter().enumerate() {
+            if *port == 0 {
+                problems.push(format!("{} must not be 0", name));
+            }
+            if let Some((other, _)) = ports[..i].iter().find(|(_, other)| other == port) {
+                problems.push(format!("{} and {} are both set to {}", other, name, port));
+            }
+        }
+        if self.discovery_port == Some(0) {
+            problems.push("discovery_port must not be 0".to_string());
+        }
+
+        if let Some(block_tip) = &self.block_tip {
+            let is_hash = block_tip.len() == 66
+                && block_tip.starts_with("0x")
+                && block_tip[2..].chars().all(|c| c.is_ascii_hexdigit());
+            if !is_hash {
+                problems.push(format!(
+                    "block_tip {:?} is not a 32-byte hex block hash",
+                    block_tip
+                ));
+            }
+        }
+
+        for peer in &self.trusted_peers {
+            if !peer.starts_with("enode://") {
+                problems.push(format!("Trusted peer {:?} is not an enode URL", peer));
+            }
+        }
+        if self.trusted_only && self.trusted_peers.is_empty() && !self.private_network {
+            problems.push("trusted_only is set but no trusted_peers are configured".to_string());
+        }
+
+        if let Some(log_driver) = self
+            .log_driver
+            .as_deref()
+            .filter(|log_driver| !LOG_DRIVERS.contains(log_driver))
+        {
+            problems.push(format!(
+                "log_driver {:?} doesn't support the max-size/max-file rotation options, use one of {}",
+                log_driver,
+                LOG_DRIVERS.join(", ")
+            ));
+        }
+        if self.log_max_file == Some(0) {
+            problems.push("log_max_file must be at least 1".to_string());
+        }
+
+        if let Some(policy) = &self.restart_policy {
+            let valid = match policy.split_once(':') {
+                Some(("on-failure", retries)) => retries.parse::<u32>().is_ok(),
+                Some(_) => false,
+                None => matches!(
+                    policy.as_str(),
+                    "no" | "always" | "unless-stopped" | "on-failure"
+                ),
+            };
+            if !valid {
+                problems.push(format!(
+                    "restart_policy {:?} is not a Docker restart policy",
+                    policy
+                ));
+            }
+        }
+
+        if let Some(mtu) = self.network_mtu.filter(|mtu| !(68..=65535).contains(mtu)) {
+            problems.push(format!("network_mtu {} is outside 68-65535", mtu));
+        }
+        if self.network_mtu.is_some() && !self.submodule_path.join(MTU_COMPOSE_FILE).is_file() {
+            problems.push(format!(
+                "network_mtu is set but {} does not contain a {}",
+                self.submodule_path.display(),
+                MTU_COMPOSE_FILE
+            ));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidConfig(problems))
         }
     }
+
+    // Environment variables used by docker-compose to interpolate the Reth service.
+    // These are set per command rather than on the process, so concurrent jobs don't race.
+    pub fn compose_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if let Some(block_tip) = &self.block_tip {
+            env.push(("RETH_TIP", block_tip.clone()));
+        }
+        if let Some(log_driver) = &self.log_driver {
+            env.push(("RETH_LOG_DRIVER", log_driver.clone()));
+        }
+        if let Some(max_size) = &self.log_max_size {
+            env.push(("RETH_LOG_MAX_SIZE", max_size.clone()));
+        }
+        if let Some(max_file) = self.log_max_file {
+            env.push(("RETH_LOG_MAX_FILE", max_file.to_string()));
+        }
+        if let Some(policy) = &self.restart_policy {
+            env.push(("RETH_RESTART_POLICY", policy.clone()));
+        }
+        // Always set, so a value exported in the operator's shell can't enable `admin`
+        let http_api = if self.admin_api {
+            HTTP_API_WITH_ADMIN
+        } else {
+            HTTP_API
+        };
+        env.push(("RETH_HTTP_API", http_api.to_string()));
+        if let Some(mtu) = self.network_mtu {
+            env.push(("RETH_NETWORK_MTU", mtu.to_string()));
+        }
+        env.push(("RETH_EXTRA_ARGS", self.reth_args().join(" ")));
+        env
+    }
 }
 
 // Context struct for Reth operations
@@ -48,14 +479,47 @@ impl RethContext {
     }
 }
 
+// Name the command in spawn failures, so e.g. a missing `docker-compose` binary isn't reported
+// as a bare "No such file or directory"
+fn spawn_error(cmd: &str) -> impl FnOnce(std::io::Error) -> std::io::Error + '_ {
+    move |e| std::io::Error::new(e.kind(), format!("Failed to run {}: {}", cmd, e))
+}
+
 // Helper function to run a command in the submodule directory
 pub fn run_command(context: &RethContext, cmd: &str, args: &[&str]) -> std::io::Result<String> {
+    run_command_with_input(context, cmd, args, None)
+}
+
+// Run a command with `input` written to its stdin. Secrets must be passed this way rather than as
+// arguments, which any local user can read with `ps` and which are logged at debug level
+pub(crate) fn run_command_with_input(
+    context: &RethContext,
+    cmd: &str,
+    args: &[&str],
+    input: Option<&str>,
+) -> std::io::Result<String> {
     debug!(command = cmd, arguments = ?args, "Running command");
 
-    let output = Command::new(cmd)
+    let mut child = Command::new(cmd)
         .current_dir(&context.config.submodule_path)
+        .envs(context.config.compose_env())
         .args(args)
-        .output()?;
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error(cmd))?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // Dropping stdin afterwards closes it, so the command sees the end of its input
+        stdin.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
 
     if output.status.success() {
         match String::from_utf8(output.stdout) {
@@ -64,8 +528,11 @@ pub fn run_command(context: &RethContext, cmd: &str, args: &[&str]) -> std::io::
                 Ok(s)
             }
             Err(e) => {
-                error!(error = %e, "Invalid UTF-8 in command output");
-                Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                error!(command = cmd, error = %e, "Invalid UTF-8 in command output");
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Invalid UTF-8 in {} output: {}", cmd, e),
+                ))
             }
         }
     } else {
@@ -73,6 +540,7 @@ pub fn run_command(context: &RethContext, cmd: &str, args: &[&str]) -> std::io::
             .unwrap_or_else(|_| "Invalid UTF-8 in stderr".to_string());
 
         error!(
+            command = cmd,
             status = %output.status,
             error = %error_message,
             "Command failed"
@@ -81,8 +549,10 @@ pub fn run_command(context: &RethContext, cmd: &str, args: &[&str]) -> std::io::
         Err(std::io::Error::new(
             std::io::ErrorKind::Other,
             format!(
-                "Command failed with status {}: {}",
-                output.status, error_message
+                "{} failed with status {}: {}",
+                cmd,
+                output.status,
+                error_message.trim()
             ),
         ))
     }
@@ -98,10 +568,12 @@ pub fn run_command_with_logs(
 
     let mut child = Command::new(cmd)
         .current_dir(&context.config.submodule_path)
+        .envs(context.config.compose_env())
         .args(args)
         .stdout(Stdio::inherit()) // Direct stdout to parent process
         .stderr(Stdio::inherit()) // Direct stderr to parent process
-        .spawn()?;
+        .spawn()
+        .map_err(spawn_error(cmd))?;
 
     // Wait for the command to finish
     let status = child.wait()?;
@@ -110,52 +582,84 @@ pub fn run_command_with_logs(
         info!("Command completed successfully");
         Ok(())
     } else {
-        error!(status = %status, "Command failed");
+        error!(command = cmd, status = %status, "Command failed");
         Err(std::io::Error::new(
             std::io::ErrorKind::Other,
-            format!("Command failed with status: {}", status),
+            format!("{} failed with status: {}", cmd, status),
         ))
     }
 }
 
+// Build the docker-compose arguments, scoping them to the configured project name and adding the
+// MTU override only when one is configured, so Docker's MTU is left alone otherwise
+fn compose_args<'a>(context: &'a RethContext, args: &[&'a str]) -> Vec<&'a str> {
+    let mut compose_args = Vec::with_capacity(args.len() + 6);
+    if let Some(project_name) = context.config.project_name.as_deref() {
+        compose_args.push("-p");
+        compose_args.push(project_name);
+    }
+    if context.config.network_mtu.is_some() {
+        compose_args.extend_from_slice(&["-f", COMPOSE_FILE, "-f", MTU_COMPOSE_FILE]);
+    }
+    compose_args.extend_from_slice(args);
+    compose_args
+}
+
+// Run a docker-compose command in the submodule directory
+pub fn run_compose(context: &RethContext, args: &[&str]) -> std::io::Result<String> {
+    run_command(context, "docker-compose", &compose_args(context, args))
+}
+
+// Run a docker-compose command and stream its output in real-time
+pub fn run_compose_with_logs(context: &RethContext, args: &[&str]) -> std::io::Result<()> {
+    run_command_with_logs(context, "docker-compose", &compose_args(context, args))
+}
+
 // Start the Reth node - This is a state-changing operation (JOB)
 #[instrument(skip(ctx), fields(block_tip = ?block_tip))]
 pub async fn reth_start(
-    Context(ctx): Context<RethContext>,
+    Context(mut ctx): Context<RethContext>,
     TangleArg(Optional(block_tip)): TangleArg<Optional<String>>,
 ) -> TangleResult<String> {
     info!("Starting Reth node");
 
-    // Set the block tip environment variable if provided
-    if let Some(block_tip) = block_tip.as_ref().or(ctx.config.block_tip.as_ref()) {
-        debug!(block_tip = %block_tip, "Setting custom block tip");
+    // A block tip passed to the job overrides the configured one, and is handed to
+    // docker-compose through the command environment
+    if let Some(block_tip) = block_tip {
+        ctx.config.block_tip = Some(block_tip);
+    }
+    if let Some(block_tip) = &ctx.config.block_tip {
+        debug!(block_tip = %block_tip, "Using custom block tip");
+    }
 
-        // Use unsafe block for the environment variable setting
-        unsafe {
-            std::env::set_var("RETH_TIP", block_tip);
-        }
+    // Catch settings docker-compose or Docker would reject only halfway through starting
+    if let Err(e) = ctx.config.validate() {
+        error!(error = %e, "Refusing to start Reth node");
+        return TangleResult(format!("Failed to start Reth node: {}", e));
+    }
+
+    match monitoring::effective_command(&ctx) {
+        Ok(command) => info!(command = %command.join(" "), "Resolved Reth command"),
+        Err(e) => debug!(error = %e, "Failed to resolve Reth command"),
     }
 
     info!("Running docker-compose up");
 
-    // First check if the containers are already running
-    let status_result = run_command(&ctx, "docker-compose", &["ps", "-q"]);
-    match status_result {
-        Ok(output) if !output.trim().is_empty() => {
-            info!("Containers already running, showing logs");
-            // Just show logs if already running
-            match run_command_with_logs(&ctx, "docker-compose", &["logs", "--follow"]) {
-                Ok(_) => {}
-                Err(e) => warn!(error = %e, "Failed to follow logs of running containers"),
-            }
+    // First check if the reth container itself is running. Stopped containers kept by `stop`,
+    // or monitoring services left running by a restore, don't count
+    if monitoring::is_running(&ctx) {
+        info!("Reth container already running, showing logs");
+        // Just show logs if already running
+        match run_compose_with_logs(&ctx, &["logs", "--follow"]) {
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "Failed to follow logs of running containers"),
         }
-        _ => {
-            // Start containers with direct log output
-            println!("\n--- Starting Reth node with Docker Compose ---");
-            if let Err(e) = run_command_with_logs(&ctx, "docker-compose", &["up"]) {
-                error!(error = %e, "Failed to start Reth node");
-                return TangleResult(format!("Failed to start Reth node: {}", e));
-            }
+    } else {
+        // Start containers with direct log output
+        println!("\n--- Starting Reth node with Docker Compose ---");
+        if let Err(e) = run_compose_with_logs(&ctx, &["up"]) {
+            error!(error = %e, "Failed to start Reth node");
+            return TangleResult(format!("Failed to start Reth node: {}", e));
         }
     }
 
@@ -177,6 +681,14 @@ pub async fn reth_start(
     ))
 }
 
+// Remove all containers and volumes of the stack, deleting the synced chain data
+pub fn clean(context: &RethContext) -> Result<()> {
+    println!("\n--- Removing Reth containers and volumes ---");
+
+    run_compose_with_logs(context, &["down", "--volumes"])
+        .map_err(Error::io("Failed to remove Reth containers and volumes"))
+}
+
 // Stop the Reth node - This is a state-changing operation (JOB)
 #[instrument(skip(ctx))]
 pub async fn reth_stop(Context(ctx): Context<RethContext>) -> TangleResult<String> {
@@ -184,12 +696,12 @@ pub async fn reth_stop(Context(ctx): Context<RethContext>) -> TangleResult<Strin
 
     println!("\n--- Stopping Reth node with Docker Compose ---");
 
-    // Run docker-compose down with direct log output
-    match run_command_with_logs(&ctx, "docker-compose", &["down", "--volumes"]) {
+    // Run docker-compose stop with direct log output, keeping containers and synced data
+    match run_compose_with_logs(&ctx, &["stop"]) {
         Ok(_) => {
             info!("Reth node stopped successfully");
             TangleResult(
-                "Reth node stopped successfully. All containers and volumes removed.".to_string(),
+                "Reth node stopped successfully. Containers and volumes were kept.".to_string(),
             )
         }
         Err(e) => {
@@ -198,3 +710,328 @@ pub async fn reth_stop(Context(ctx): Context<RethContext>) -> TangleResult<Strin
         }
     }
 }
+
+// Get the transaction pool status - This is a read-only operation (JOB)
+#[instrument(skip(ctx))]
+pub async fn reth_txpool_status(Context(ctx): Context<RethContext>) -> TangleResult<String> {
+    info!("Querying Reth transaction pool status");
+
+    match monitoring::get_txpool_status(&ctx) {
+        Ok(status) => TangleResult(status.to_string()),
+        Err(e) => {
+            error!(error = %e, "Failed to query txpool status");
+            TangleResult(format!("Failed to query txpool status: {}", e))
+        }
+    }
+}
+
+// Get the latest and finalized block heads - This is a read-only operation (JOB)
+#[instrument(skip(ctx))]
+pub async fn reth_head_info(Context(ctx): Context<RethContext>) -> TangleResult<String> {
+    info!("Querying Reth head info");
+
+    match monitoring::get_head_info(&ctx) {
+        Ok(head_info) => TangleResult(head_info.to_string()),
+        Err(e) => {
+            error!(error = %e, "Failed to query head info");
+            TangleResult(format!("Failed to query head info: {}", e))
+        }
+    }
+}
+
+// Forward an allowlisted JSON-RPC call to the node - This is a read-only operation (JOB)
+#[instrument(skip(ctx))]
+pub async fn reth_rpc_passthrough(
+    Context(ctx): Context<RethContext>,
+    TangleArg(request): TangleArg<String>,
+) -> TangleResult<String> {
+    info!("Forwarding RPC request");
+
+    match monitoring::rpc_passthrough(&ctx, &request) {
+        Ok(response) => TangleResult(response.to_string()),
+        Err(e) => {
+            error!(error = %e, "Failed to forward RPC request");
+            TangleResult(format!("Failed to forward RPC request: {}", e))
+        }
+    }
+}
+
+// Add or remove a peer from a `{ "action": "add" | "remove", "enode": ... }` request
+fn manage_peer(context: &RethContext, request: &str) -> Result<String> {
+    if !context.config.admin_api {
+        return Err(Error::Other(
+            "The admin RPC namespace is disabled, set RETH_ADMIN_API to manage peers".to_string(),
+        ));
+    }
+
+    let request: serde_json::Value =
+        serde_json::from_str(request).map_err(Error::parse("Invalid peer request"))?;
+
+    let enode = request["enode"]
+        .as_str()
+        .ok_or_else(|| Error::Other("Peer request is missing an enode".to_string()))?;
+
+    let action = request["action"].as_str().unwrap_or_default();
+    let method = match action {
+        "add" => "admin_addPeer",
+        "remove" => "admin_removePeer",
+        _ => {
+            return Err(Error::Other(format!(
+                "Unknown peer action {:?}, expected \"add\" or \"remove\"",
+                action
+            )));
+        }
+    };
+
+    let result = monitoring::rpc_request(context, method, json!([enode]))?;
+
+    Ok(json!({
+        "action": action,
+        "enode": enode,
+        "success": result.as_bool().unwrap_or(false),
+    })
+    .to_string())
+}
+
+// Add or remove a peer without restarting the node - This is a state-changing operation (JOB)
+#[instrument(skip(ctx))]
+pub async fn reth_manage_peers(
+    Context(ctx): Context<RethContext>,
+    TangleArg(request): TangleArg<String>,
+) -> TangleResult<String> {
+    info!("Managing Reth peers");
+
+    match manage_peer(&ctx, &request) {
+        Ok(result) => TangleResult(result),
+        Err(e) => {
+            error!(error = %e, "Failed to manage peers");
+            TangleResult(format!("Failed to manage peers: {}", e))
+        }
+    }
+}
+
+// Report service health, sync progress, peers and client version at once - This is a read-only operation (JOB)
+#[instrument(skip(ctx))]
+pub async fn reth_stack_status(Context(ctx): Context<RethContext>) -> TangleResult<String> {
+    info!("Querying Reth stack status");
+
+    match monitoring::get_stack_status(&ctx) {
+        Ok(stack_status) => TangleResult(stack_status.to_string()),
+        Err(e) => {
+            error!(error = %e, "Failed to query stack status");
+            TangleResult(format!("Failed to query stack status: {}", e))
+        }
+    }
+}
+
+// Get the current gas price and fee suggestions - This is a read-only operation (JOB)
+#[instrument(skip(ctx))]
+pub async fn reth_gas_price(Context(ctx): Context<RethContext>) -> TangleResult<String> {
+    info!("Querying Reth gas price");
+
+    match monitoring::get_gas_price(&ctx) {
+        Ok(gas_price) => TangleResult(gas_price.to_string()),
+        Err(e) => {
+            error!(error = %e, "Failed to query gas price");
+            TangleResult(format!("Failed to query gas price: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A config pointing at the repository's compose directory, so it passes validation
+    fn valid_config() -> RethConfig {
+        RethConfig {
+            submodule_path: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../reth_docker"),
+            ..RethConfig::default()
+        }
+    }
+
+    fn problems(config: &RethConfig) -> Vec<String> {
+        match config.validate() {
+            Ok(()) => Vec::new(),
+            Err(Error::InvalidConfig(problems)) => problems,
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn default_config_has_no_extra_args() {
+        assert!(RethConfig::default().reth_args().is_empty());
+    }
+
+    #[test]
+    fn reth_args_pass_values_after_their_flags() {
+        let config = RethConfig {
+            rpc_gas_cap: Some(100_000_000),
+            discovery_port: Some(30304),
+            trusted_peers: vec![
+                "enode://a@1.2.3.4:30303".into(),
+                "enode://b@5.6.7.8:30303".into(),
+            ],
+            ..RethConfig::default()
+        };
+
+        assert_eq!(config.reth_args(), [
+            "--rpc.gascap",
+            "100000000",
+            "--trusted-peers",
+            "enode://a@1.2.3.4:30303,enode://b@5.6.7.8:30303",
+            "--discovery.port",
+            "30304",
+        ]);
+    }
+
+    #[test]
+    fn private_network_implies_trusted_only_without_dns_discovery_flag() {
+        let config = RethConfig {
+            private_network: true,
+            trusted_only: true,
+            disable_dns_discovery: true,
+            ..RethConfig::default()
+        };
+
+        assert_eq!(config.reth_args(), [
+            "--trusted-only",
+            "--disable-discovery"
+        ]);
+    }
+
+    #[test]
+    fn dns_discovery_can_be_disabled_on_its_own() {
+        let config = RethConfig {
+            disable_dns_discovery: true,
+            ..RethConfig::default()
+        };
+
+        assert_eq!(config.reth_args(), ["--disable-dns-discovery"]);
+    }
+
+    #[test]
+    fn compose_env_enables_admin_api() {
+        let env = RethConfig {
+            admin_api: true,
+            ..RethConfig::default()
+        }
+        .compose_env();
+
+        assert!(env.contains(&("RETH_HTTP_API", HTTP_API_WITH_ADMIN.to_string())));
+
+        let env = RethConfig::default().compose_env();
+        assert!(env.contains(&("RETH_HTTP_API", "eth,net,web3,txpool".to_string())));
+        assert!(env.contains(&("RETH_EXTRA_ARGS", String::new())));
+    }
+
+    #[test]
+    fn compose_args_add_mtu_override_only_when_set() {
+        let context = RethContext::new(RethConfig {
+            project_name: Some("reth-test".to_string()),
+            ..RethConfig::default()
+        });
+        assert_eq!(compose_args(&context, &["up"]), ["-p", "reth-test", "up"]);
+
+        let context = RethContext::new(RethConfig {
+            network_mtu: Some(1400),
+            ..RethConfig::default()
+        });
+        assert_eq!(compose_args(&context, &["up"]), [
+            "-f",
+            COMPOSE_FILE,
+            "-f",
+            MTU_COMPOSE_FILE,
+            "up"
+        ]);
+    }
+
+    #[test]
+    fn valid_config_passes() {
+        assert_eq!(problems(&valid_config()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn missing_compose_file_is_reported() {
+        let config = RethConfig {
+            submodule_path: PathBuf::from("does-not-exist"),
+            ..RethConfig::default()
+        };
+
+        assert_eq!(problems(&config), [
+            "does-not-exist does not contain a docker-compose.yml"
+        ]);
+    }
+
+    #[test]
+    fn colliding_ports_are_reported_once_per_collision() {
+        let config = RethConfig {
+            grafana_port: 9000,
+            rpc_port: 9000,
+            ..valid_config()
+        };
+
+        assert_eq!(problems(&config), [
+            "monitoring_port and grafana_port are both set to 9000",
+            "monitoring_port and rpc_port are both set to 9000",
+        ]);
+    }
+
+    #[test]
+    fn restart_policies_are_checked() {
+        for policy in [
+            "no",
+            "always",
+            "unless-stopped",
+            "on-failure",
+            "on-failure:5",
+        ] {
+            let config = RethConfig {
+                restart_policy: Some(policy.to_string()),
+                ..valid_config()
+            };
+            assert!(problems(&config).is_empty(), "{} should be valid", policy);
+        }
+
+        for policy in ["sometimes", "on-failure:", "on-failure:x", "always:3"] {
+            let config = RethConfig {
+                restart_policy: Some(policy.to_string()),
+                ..valid_config()
+            };
+            assert_eq!(problems(&config).len(), 1, "{} should be invalid", policy);
+        }
+    }
+
+    #[test]
+    fn invalid_settings_are_all_reported() {
+        let config = RethConfig {
+            block_tip: Some("0x1234".to_string()),
+            trusted_peers: vec!["1.2.3.4:30303".to_string()],
+            log_driver: Some("journald".to_string()),
+            log_max_file: Some(0),
+            network_mtu: Some(10),
+            ..valid_config()
+        };
+
+        assert_eq!(problems(&config).len(), 5);
+    }
+
+    #[test]
+    fn block_tip_must_be_a_block_hash() {
+        let config = RethConfig {
+            block_tip: Some(
+                "0x7d5a4369273c723454ac137f48a4f142b097aa2779464e6505f1b1c5e37b5382".to_string(),
+            ),
+            ..valid_config()
+        };
+        assert!(problems(&config).is_empty());
+
+        let config = RethConfig {
+            block_tip: Some(
+                "0xzz5a4369273c723454ac137f48a4f142b097aa2779464e6505f1b1c5e37b5382".to_string(),
+            ),
+            ..valid_config()
+        };
+        assert_eq!(problems(&config).len(), 1);
+    }
+}
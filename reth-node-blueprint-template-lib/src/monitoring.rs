@@ -1,30 +1,349 @@
-use crate::{RethContext, run_command, run_command_with_logs};
+use crate::backup::{HELPER_IMAGE, data_volume_name, volume_exists};
+use crate::{
+    Error, Result, RethContext, RpcAuth, run_command, run_command_with_input,
+    run_command_with_logs, run_compose, run_compose_with_logs,
+};
+use serde::Serialize;
+use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::io;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
 /// Get status of Reth node
-pub fn get_status(context: &RethContext) -> Result<String, String> {
+pub fn get_status(context: &RethContext) -> Result<String> {
     println!("\n--- Checking Reth node status ---");
 
     // First try running with direct console output
-    let _ = run_command_with_logs(context, "docker-compose", &["ps"]);
+    let _ = run_compose_with_logs(context, &["ps"]);
 
     // Then get the output as string for return value
-    match run_command(context, "docker-compose", &["ps"]) {
+    match run_compose(context, &["ps"]) {
         Ok(output) => {
             if output.trim().is_empty() {
                 Ok("No Reth services are currently running.".to_string())
             } else {
-                Ok(format!("Reth services status:\n{}", output))
+                let mut status = format!("Reth services status:\n{}", output);
+                // The image is tagged by branch, so only the RPC reports the actual running version
+                if let Ok(version) = get_client_version(context) {
+                    status.push_str(&format!("\nReth client version: {}", version));
+                }
+                Ok(status)
             }
         }
-        Err(e) => Err(format!("Failed to get Reth status: {}", e)),
+        Err(source) => Err(Error::Io {
+            context: "Failed to get Reth status".to_string(),
+            source,
+        }),
+    }
+}
+
+/// A host port published by a docker-compose service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortMapping {
+    pub host_ip: String,
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: String,
+}
+
+/// Status of a single docker-compose service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub container: String,
+    pub state: String,
+    /// Docker healthcheck status (`starting`, `healthy` or `unhealthy`), if the service defines one
+    pub health: Option<String>,
+    pub ports: Vec<PortMapping>,
+}
+
+impl ServiceStatus {
+    /// Whether the service's container is currently running
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+
+    fn from_compose_json(entry: &Value) -> Self {
+        let ports = entry["Publishers"]
+            .as_array()
+            .map(|publishers| {
+                publishers
+                    .iter()
+                    // Exposed but unpublished ports are reported with a published port of 0
+                    .filter(|publisher| publisher["PublishedPort"].as_u64().unwrap_or(0) != 0)
+                    .map(|publisher| PortMapping {
+                        host_ip: publisher["URL"].as_str().unwrap_or_default().to_string(),
+                        host_port: publisher["PublishedPort"].as_u64().unwrap_or(0) as u16,
+                        container_port: publisher["TargetPort"].as_u64().unwrap_or(0) as u16,
+                        protocol: publisher["Protocol"].as_str().unwrap_or("tcp").to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            name: entry["Service"].as_str().unwrap_or_default().to_string(),
+            container: entry["Name"].as_str().unwrap_or_default().to_string(),
+            state: entry["State"].as_str().unwrap_or_default().to_string(),
+            health: entry["Health"]
+                .as_str()
+                .filter(|health| !health.is_empty())
+                .map(str::to_string),
+            ports,
+        }
+    }
+
+    fn from_inspect_json(entry: &Value) -> Self {
+        // Ports are keyed by `<port>/<protocol>`, with no bindings for unpublished ones
+        let ports = entry["NetworkSettings"]["Ports"]
+            .as_object()
+            .map(|ports| {
+                ports
+                    .iter()
+                    .flat_map(|(target, bindings)| {
+                        let (port, protocol) =
+                            target.split_once('/').unwrap_or((target.as_str(), "tcp"));
+                        let container_port = port.parse().unwrap_or(0);
+                        bindings
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .map(move |binding| PortMapping {
+                                host_ip: binding["HostIp"].as_str().unwrap_or_default().to_string(),
+                                host_port: binding["HostPort"]
+                                    .as_str()
+                                    .and_then(|port| port.parse().ok())
+                                    .unwrap_or(0),
+                                container_port,
+                                protocol: protocol.to_string(),
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            name: entry["Config"]["Labels"]["com.docker.compose.service"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            container: entry["Name"]
+                .as_str()
+                .unwrap_or_default()
+                .trim_start_matches('/')
+                .to_string(),
+            state: entry["State"]["Status"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            health: entry["State"]["Health"]["Status"]
+                .as_str()
+                .map(str::to_string),
+            ports,
+        }
+    }
+}
+
+/// Get the structured status of every docker-compose service, including stopped ones
+pub fn get_service_statuses(context: &RethContext) -> Result<Vec<ServiceStatus>> {
+    let output = match run_compose(context, &["ps", "--all", "--format", "json"]) {
+        Ok(output) => output,
+        Err(e) => {
+            debug!(error = %e, "docker-compose ps has no JSON output, using docker inspect");
+            return get_service_statuses_from_inspect(context);
+        }
+    };
+
+    parse_compose_ps(&output)
+}
+
+/// Parse the JSON output of `docker-compose ps --format json`
+fn parse_compose_ps(output: &str) -> Result<Vec<ServiceStatus>> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Older Compose v2 releases print a single JSON array, newer ones print one object per line
+    let entries: Vec<Value> = if output.starts_with('[') {
+        serde_json::from_str(output)
+            .map_err(Error::parse("Failed to parse docker-compose ps output"))?
+    } else {
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()
+            .map_err(Error::parse("Failed to parse docker-compose ps output"))?
+    };
+
+    Ok(entries
+        .iter()
+        .map(ServiceStatus::from_compose_json)
+        .collect())
+}
+
+/// Get the service statuses from `docker inspect`, for docker-compose releases (v1) whose `ps`
+/// has no JSON output. `ps -q` there lists stopped containers as well
+fn get_service_statuses_from_inspect(context: &RethContext) -> Result<Vec<ServiceStatus>> {
+    let output =
+        run_compose(context, &["ps", "-q"]).map_err(Error::io("Failed to get Reth status"))?;
+
+    let mut args = vec!["inspect"];
+    args.extend(output.split_whitespace());
+    if args.len() == 1 {
+        return Ok(Vec::new());
+    }
+
+    let output = run_command(context, "docker", &args)
+        .map_err(Error::io("Failed to inspect Reth containers"))?;
+    let entries: Vec<Value> = serde_json::from_str(&output)
+        .map_err(Error::parse("Failed to parse docker inspect output"))?;
+
+    Ok(entries
+        .iter()
+        .map(ServiceStatus::from_inspect_json)
+        .collect())
+}
+
+/// CPU and memory usage of a running container, as reported by `docker stats`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceUsage {
+    pub cpu: String,
+    pub memory: String,
+}
+
+/// Get the resource usage of each running service, keyed by service name
+pub fn get_resource_usage(context: &RethContext) -> Result<HashMap<String, ResourceUsage>> {
+    let statuses = get_service_statuses(context)?;
+    let running: Vec<&ServiceStatus> = statuses.iter().filter(|s| s.is_running()).collect();
+
+    if running.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut args = vec!["stats", "--no-stream", "--format", "{{json .}}"];
+    args.extend(running.iter().map(|status| status.container.as_str()));
+
+    let output = run_command(context, "docker", &args)
+        .map_err(Error::io("Failed to get container stats"))?;
+
+    let mut usage = HashMap::new();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: Value = serde_json::from_str(line)
+            .map_err(Error::parse("Failed to parse docker stats output"))?;
+
+        let Some(status) = running
+            .iter()
+            .find(|status| entry["Name"].as_str() == Some(status.container.as_str()))
+        else {
+            continue;
+        };
+
+        usage.insert(status.name.clone(), ResourceUsage {
+            cpu: entry["CPUPerc"].as_str().unwrap_or_default().to_string(),
+            memory: entry["MemUsage"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+
+    Ok(usage)
+}
+
+/// Get the full `docker inspect` output for a service's container, as recorded by Docker
+pub fn inspect_service(context: &RethContext, service: &str) -> Result<Value> {
+    let container_id = run_compose(context, &["ps", "-q", service]).map_err(Error::io(format!(
+        "Failed to find container for {}",
+        service
+    )))?;
+    let container_id = container_id.trim();
+
+    if container_id.is_empty() {
+        return Err(Error::NotRunning(format!(
+            "Service {} has no container",
+            service
+        )));
+    }
+
+    let output = run_command(context, "docker", &["inspect", container_id])
+        .map_err(Error::io(format!("Failed to inspect {}", service)))?;
+
+    // `docker inspect` always returns an array, with one entry per inspected object
+    let mut inspect: Value = serde_json::from_str(&output)
+        .map_err(Error::parse("Failed to parse docker inspect output"))?;
+
+    Ok(inspect[0].take())
+}
+
+/// Get the command the Reth container is launched with, as resolved by docker-compose from the
+/// compose file and the configured environment
+pub fn effective_command(context: &RethContext) -> Result<Vec<String>> {
+    let output = run_compose(context, &["config", "--format", "json"])
+        .map_err(Error::io("Failed to read docker-compose config"))?;
+    let config: Value = serde_json::from_str(&output)
+        .map_err(Error::parse("Failed to parse docker-compose config"))?;
+
+    let command = &config["services"]["reth"]["command"];
+    match command {
+        Value::Array(args) => Ok(args
+            .iter()
+            .filter_map(|arg| arg.as_str().map(str::to_string))
+            .collect()),
+        // Older Compose releases keep a string command as written
+        Value::String(command) => Ok(command.split_whitespace().map(str::to_string).collect()),
+        _ => Err(Error::Other(format!(
+            "Unexpected reth command in docker-compose config: {}",
+            command
+        ))),
     }
 }
 
-/// Get logs from the Reth node
-pub fn get_logs(context: &RethContext, lines: Option<usize>) -> Result<String, String> {
+/// Get the disk space used by the Reth datadir volume, in bytes
+pub fn datadir_size(context: &RethContext) -> Result<u64> {
+    let volume = data_volume_name(context)?;
+    // Mounting a missing volume would create an empty one and report a size of 0
+    if !volume_exists(context, &volume)? {
+        return Err(Error::NotRunning(format!(
+            "Datadir volume {} does not exist, start the Reth node first",
+            volume
+        )));
+    }
+    let data_mount = format!("{}:/data:ro", volume);
+
+    // Busybox `du` has no byte-granular output, so report in KiB and convert
+    let output = run_command(context, "docker", &[
+        "run",
+        "--rm",
+        "-v",
+        &data_mount,
+        HELPER_IMAGE,
+        "du",
+        "-sk",
+        "/data",
+    ])
+    .map_err(Error::io(format!("Failed to measure {}", volume)))?;
+
+    parse_du_size(&output)
+}
+
+/// Parse the size in bytes from `du -sk` output, e.g. `1234\t/data`
+fn parse_du_size(output: &str) -> Result<u64> {
+    output
+        .split_whitespace()
+        .next()
+        .and_then(|kib| kib.parse::<u64>().ok())
+        .map(|kib| kib * 1024)
+        .ok_or_else(|| Error::Other(format!("Unexpected du output: {}", output.trim())))
+}
+
+/// Get logs from the Reth node, optionally limited to a time window. `since` and `until` accept
+/// anything `docker-compose logs` does, e.g. a timestamp (`2024-01-02T13:23:37Z`) or a relative
+/// duration (`42m`)
+pub fn get_logs(
+    context: &RethContext,
+    lines: Option<usize>,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<String> {
     println!("\n--- Fetching Reth node logs ---");
 
     // Create command arguments with owned strings
@@ -35,16 +354,26 @@ pub fn get_logs(context: &RethContext, lines: Option<usize>) -> Result<String, S
         cmd_args.push(lines.to_string());
     }
 
+    if let Some(since) = since {
+        cmd_args.push("--since".to_string());
+        cmd_args.push(since.to_string());
+    }
+
+    if let Some(until) = until {
+        cmd_args.push("--until".to_string());
+        cmd_args.push(until.to_string());
+    }
+
     cmd_args.push("reth".to_string());
 
     // Convert to string slice references for the command
     let args: Vec<&str> = cmd_args.iter().map(|s| s.as_str()).collect();
 
     // First show logs directly to console
-    let _ = run_command_with_logs(context, "docker-compose", &args);
+    let _ = run_compose_with_logs(context, &args);
 
     // Then get output as string for return
-    match run_command(context, "docker-compose", &args) {
+    match run_compose(context, &args) {
         Ok(output) => {
             if output.trim().is_empty() {
                 Ok("No logs available from Reth node.".to_string())
@@ -52,21 +381,28 @@ pub fn get_logs(context: &RethContext, lines: Option<usize>) -> Result<String, S
                 Ok(format!("Reth node logs:\n{}", output))
             }
         }
-        Err(e) => Err(format!("Failed to get Reth logs: {}", e)),
+        Err(source) => Err(Error::Io {
+            context: "Failed to get Reth logs".to_string(),
+            source,
+        }),
     }
 }
 
 /// Check if Grafana is ready and return the URL
-pub fn check_grafana_ready(context: &RethContext) -> Result<String, String> {
+pub fn check_grafana_ready(context: &RethContext) -> Result<String> {
     println!("\n--- Checking Grafana status ---");
 
     // Display status directly to console
-    let _ = run_command_with_logs(context, "docker-compose", &["ps", "grafana"]);
+    let _ = run_compose_with_logs(context, &["ps", "grafana"]);
 
     // Check if Grafana container is running
-    match run_command(context, "docker-compose", &["ps", "grafana"]) {
-        Ok(status) => {
-            if status.contains("Up") {
+    match get_service_statuses(context) {
+        Ok(statuses) => {
+            let running = statuses
+                .iter()
+                .any(|status| status.name == "grafana" && status.is_running());
+
+            if running {
                 Ok(format!(
                     "Grafana is running and available at http://localhost:{}\n\
                     Login with username: admin, password: admin\n\
@@ -74,35 +410,40 @@ pub fn check_grafana_ready(context: &RethContext) -> Result<String, String> {
                     context.config.grafana_port
                 ))
             } else {
-                Err("Grafana is not running. Please start the Reth node first.".to_string())
+                Err(Error::NotRunning(
+                    "Grafana is not running. Please start the Reth node first.".to_string(),
+                ))
             }
         }
-        Err(e) => Err(format!("Failed to check Grafana status: {}", e)),
+        Err(e) => Err(e),
     }
 }
 
 /// Get metrics from the Prometheus metrics endpoint
-pub fn get_metrics(context: &RethContext) -> Result<HashMap<String, String>, String> {
+///
+/// Fails if the Reth node is not running. If the node is up but its metrics endpoint doesn't
+/// respond (e.g. metrics are still starting), an empty map is returned and a warning is logged.
+pub fn get_metrics(context: &RethContext) -> Result<HashMap<String, String>> {
     println!("\n--- Fetching metrics from Prometheus ---");
 
     // First check if the Reth node is running
-    let running = match get_status(context) {
-        Ok(status) => !status.contains("No Reth services"),
-        Err(_) => false,
-    };
-
-    if !running {
-        return Err("Reth node is not running. Please start it first.".to_string());
+    if !is_running(context) {
+        return Err(Error::NotRunning(
+            "Reth node is not running. Please start it first.".to_string(),
+        ));
     }
 
     // Use curl to get metrics with direct output
     let endpoint = format!("localhost:{}", context.config.monitoring_port);
 
+    let timeout = curl_timeout(context.config.rpc_timeout);
+    let args = ["-s", "--max-time", &timeout, &endpoint];
+
     // Show some metrics directly to console
-    let _ = run_command_with_logs(context, "curl", &["-s", &endpoint]);
+    let _ = run_command_with_logs(context, "curl", &args);
 
     // Parse metrics for return value
-    match run_command(context, "curl", &["-s", &endpoint]) {
+    match run_command(context, "curl", &args) {
         Ok(output) => {
             // Parse the Prometheus metrics format
             let mut metrics = HashMap::new();
@@ -122,7 +463,15 @@ pub fn get_metrics(context: &RethContext) -> Result<HashMap<String, String>, Str
 
             Ok(metrics)
         }
-        Err(e) => Err(format!("Failed to get metrics: {}", e)),
+        Err(e) => {
+            // The node itself is up, so only the metrics endpoint is unavailable
+            warn!(
+                error = %e,
+                endpoint = %endpoint,
+                "Metrics endpoint is not responding, returning no metrics"
+            );
+            Ok(HashMap::new())
+        }
     }
 }
 
@@ -142,6 +491,10 @@ pub fn get_service_urls(context: &RethContext) -> HashMap<String, String> {
         "metrics".to_string(),
         format!("http://localhost:{}", context.config.monitoring_port),
     );
+    urls.insert(
+        "rpc".to_string(),
+        format!("http://localhost:{}", context.config.rpc_port),
+    );
 
     println!("\n--- Service URLs ---");
     for (service, url) in &urls {
@@ -150,3 +503,627 @@ pub fn get_service_urls(context: &RethContext) -> HashMap<String, String> {
 
     urls
 }
+
+/// Shortest timeout passed to curl, as `--max-time 0` would disable the limit altogether
+const MIN_CURL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Format a timeout as a `curl --max-time` argument
+fn curl_timeout(timeout: Duration) -> String {
+    format!("{:.3}", timeout.max(MIN_CURL_TIMEOUT).as_secs_f64())
+}
+
+/// Quote a value for a curl config file (see `curl --config`)
+fn curl_config_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Send a JSON-RPC request to the Reth HTTP endpoint and return the raw response
+pub fn rpc_request_raw(context: &RethContext, method: &str, params: Value) -> Result<Value> {
+    debug!(method = method, "Sending RPC request");
+
+    let endpoint = format!("http://localhost:{}", context.config.rpc_port);
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    })
+    .to_string();
+
+    // Fail on HTTP errors so a rejected request (e.g. by an auth proxy) isn't parsed as JSON-RPC
+    let mut args = vec![
+        "-sf".to_string(),
+        "--max-time".to_string(),
+        curl_timeout(context.config.rpc_timeout),
+        "-X".to_string(),
+        "POST".to_string(),
+        "-H".to_string(),
+        "Content-Type: application/json".to_string(),
+    ];
+
+    // Credentials are handed to curl as a config on stdin, keeping them out of its arguments
+    let auth_config = match &context.config.rpc_auth {
+        Some(RpcAuth::Bearer(token)) => Some(format!(
+            "header = {}\n",
+            curl_config_value(&format!("Authorization: Bearer {}", token))
+        )),
+        Some(RpcAuth::Basic { username, password }) => Some(format!(
+            "user = {}\n",
+            curl_config_value(&format!("{}:{}", username, password))
+        )),
+        None => None,
+    };
+    if auth_config.is_some() {
+        args.push("-K".to_string());
+        args.push("-".to_string());
+    }
+    args.push("--data".to_string());
+    args.push(body);
+    args.push(endpoint.clone());
+
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = run_command_with_input(context, "curl", &args, auth_config.as_deref()).map_err(
+        Error::io(format!("Failed to reach Reth RPC at {}", endpoint)),
+    )?;
+
+    serde_json::from_str(&output)
+        .map_err(Error::parse(format!("Invalid RPC response for {}", method)))
+}
+
+/// Send a JSON-RPC request to the Reth HTTP endpoint and return the `result` field
+pub fn rpc_request(context: &RethContext, method: &str, params: Value) -> Result<Value> {
+    let mut response = rpc_request_raw(context, method, params)?;
+
+    if let Some(error) = response.get_mut("error") {
+        return Err(Error::Rpc {
+            method: method.to_string(),
+            error: error.take(),
+        });
+    }
+
+    Ok(response["result"].take())
+}
+
+/// Parse a hex quantity (e.g. `"0x1a"`) as returned by the Ethereum JSON-RPC API
+pub fn parse_hex_quantity(value: &Value) -> Option<u64> {
+    let hex = value.as_str()?.trim_start_matches("0x");
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// Get the client version reported by the running Reth node
+pub fn get_client_version(context: &RethContext) -> Result<String> {
+    let version = rpc_request(context, "web3_clientVersion", json!([]))?;
+
+    version.as_str().map(str::to_string).ok_or_else(|| {
+        Error::Other(format!(
+            "Unexpected web3_clientVersion response: {}",
+            version
+        ))
+    })
+}
+
+/// Get the number of pending and queued transactions in the Reth transaction pool
+pub fn get_txpool_status(context: &RethContext) -> Result<Value> {
+    let status = rpc_request(context, "txpool_status", json!([]))?;
+
+    let pending = parse_hex_quantity(&status["pending"])
+        .ok_or_else(|| Error::Other(format!("Unexpected txpool_status response: {}", status)))?;
+    let queued = parse_hex_quantity(&status["queued"])
+        .ok_or_else(|| Error::Other(format!("Unexpected txpool_status response: {}", status)))?;
+
+    Ok(json!({
+        "pending": pending,
+        "queued": queued,
+    }))
+}
+
+/// Blocks of fee history the priority fee suggestions are averaged over
+const FEE_HISTORY_BLOCKS: u64 = 5;
+
+/// Reward percentiles used as the low, medium and high priority fee suggestions
+const FEE_HISTORY_PERCENTILES: [u64; 3] = [25, 50, 75];
+
+/// Get the current gas price along with the next block's base fee and priority fee suggestions,
+/// all in wei
+pub fn get_gas_price(context: &RethContext) -> Result<Value> {
+    let gas_price = rpc_request(context, "eth_gasPrice", json!([]))?;
+    let max_priority_fee = rpc_request(context, "eth_maxPriorityFeePerGas", json!([]))?;
+    let fee_history = rpc_request(
+        context,
+        "eth_feeHistory",
+        json!([
+            format!("0x{:x}", FEE_HISTORY_BLOCKS),
+            "latest",
+            FEE_HISTORY_PERCENTILES
+        ]),
+    )?;
+
+    let unexpected = || {
+        Error::Other(format!(
+            "Unexpected eth_feeHistory response: {}",
+            fee_history
+        ))
+    };
+
+    // `baseFeePerGas` includes one extra entry, the base fee of the next block
+    let base_fee = fee_history["baseFeePerGas"]
+        .as_array()
+        .and_then(|base_fees| base_fees.last())
+        .and_then(parse_hex_quantity)
+        .ok_or_else(unexpected)?;
+    let rewards = fee_history["reward"].as_array().ok_or_else(unexpected)?;
+
+    let mut priority_fees = serde_json::Map::new();
+    for (i, (name, percentile)) in ["low", "medium", "high"]
+        .into_iter()
+        .zip(FEE_HISTORY_PERCENTILES)
+        .enumerate()
+    {
+        let fees: Vec<u64> = rewards
+            .iter()
+            .filter_map(|block| parse_hex_quantity(&block[i]))
+            .collect();
+        let average = match fees.len() {
+            0 => None,
+            n => Some(fees.iter().sum::<u64>() / n as u64),
+        };
+        priority_fees.insert(
+            name.to_string(),
+            json!({ "percentile": percentile, "fee": average }),
+        );
+    }
+
+    Ok(json!({
+        "gas_price": parse_hex_quantity(&gas_price),
+        "base_fee": base_fee,
+        "max_priority_fee": parse_hex_quantity(&max_priority_fee),
+        "priority_fees": priority_fees,
+    }))
+}
+
+/// Sync progress and connectivity of the Reth node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncStatus {
+    pub syncing: bool,
+    pub current_block: Option<u64>,
+    /// Target block of the current sync, `None` once the node has caught up
+    pub highest_block: Option<u64>,
+    pub peer_count: Option<u64>,
+}
+
+/// Get the sync progress and peer count of the Reth node
+pub fn get_sync_status(context: &RethContext) -> Result<SyncStatus> {
+    let syncing = rpc_request(context, "eth_syncing", json!([]))?;
+    let current_block = rpc_request(context, "eth_blockNumber", json!([]))?;
+    let peer_count = rpc_request(context, "net_peerCount", json!([]))?;
+
+    // `eth_syncing` returns `false` once caught up, and a progress object while syncing
+    Ok(SyncStatus {
+        syncing: syncing != Value::Bool(false),
+        current_block: parse_hex_quantity(&current_block),
+        highest_block: parse_hex_quantity(&syncing["highestBlock"]),
+        peer_count: parse_hex_quantity(&peer_count),
+    })
+}
+
+/// Check whether the Reth container is running
+pub fn is_running(context: &RethContext) -> bool {
+    match get_service_statuses(context) {
+        Ok(statuses) => reth_is_running(&statuses),
+        Err(e) => {
+            debug!(error = %e, "Failed to get service statuses");
+            false
+        }
+    }
+}
+
+/// Whether the `reth` service is among the running ones. Other services (or stopped containers
+/// kept by `stop`) don't count
+fn reth_is_running(statuses: &[ServiceStatus]) -> bool {
+    statuses
+        .iter()
+        .any(|status| status.name == "reth" && status.is_running())
+}
+
+/// Check whether the Reth container is running, passes its compose healthcheck (if it defines
+/// one), and its RPC endpoint is responding
+pub fn is_healthy(context: &RethContext) -> bool {
+    let container_healthy = match get_service_statuses(context) {
+        Ok(statuses) => statuses.iter().any(|status| {
+            status.name == "reth"
+                && status.is_running()
+                && status
+                    .health
+                    .as_deref()
+                    .is_none_or(|health| health == "healthy")
+        }),
+        Err(e) => {
+            debug!(error = %e, "Failed to get service statuses");
+            false
+        }
+    };
+
+    container_healthy && rpc_request(context, "eth_blockNumber", json!([])).is_ok()
+}
+
+/// Block until the Reth node is running (and healthy, if `require_healthy` is set), or the
+/// timeout elapses
+pub fn wait_for_node(
+    context: &RethContext,
+    timeout: Duration,
+    require_healthy: bool,
+) -> Result<()> {
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    let deadline = Instant::now() + timeout;
+    let mut bounded_context = context.clone();
+    loop {
+        // Keep a slow RPC request from running past the deadline
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        bounded_context.config.rpc_timeout = context.config.rpc_timeout.min(remaining);
+
+        let ready = if require_healthy {
+            is_healthy(&bounded_context)
+        } else {
+            is_running(&bounded_context)
+        };
+
+        if ready {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            let condition = if require_healthy {
+                "healthy"
+            } else {
+                "running"
+            };
+            return Err(Error::Other(format!(
+                "Reth node was not {} after {} seconds",
+                condition,
+                timeout.as_secs()
+            )));
+        }
+
+        debug!("Reth node not ready yet, retrying");
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Summarize a block returned by `eth_getBlockByNumber`, or `null` if the block is unknown
+fn block_summary(block: &Value) -> Value {
+    if block.is_null() {
+        return Value::Null;
+    }
+
+    json!({
+        "hash": block["hash"],
+        "number": parse_hex_quantity(&block["number"]),
+        "timestamp": parse_hex_quantity(&block["timestamp"]),
+        "base_fee_per_gas": parse_hex_quantity(&block["baseFeePerGas"]),
+    })
+}
+
+/// Get the latest and finalized block heads of the Reth node
+pub fn get_head_info(context: &RethContext) -> Result<Value> {
+    let latest = rpc_request(context, "eth_getBlockByNumber", json!(["latest", false]))?;
+    let finalized = rpc_request(context, "eth_getBlockByNumber", json!(["finalized", false]))?;
+
+    Ok(json!({
+        "latest": block_summary(&latest),
+        "finalized": block_summary(&finalized),
+    }))
+}
+
+/// Get the state of every service together with the node's sync progress, client version and
+/// datadir size. The RPC fields are `null` when the node isn't answering, and the size when the
+/// datadir volume doesn't exist yet
+pub fn get_stack_status(context: &RethContext) -> Result<Value> {
+    let services = get_service_statuses(context)?;
+
+    let sync = get_sync_status(context)
+        .map_err(|e| debug!(error = %e, "Failed to get sync status"))
+        .ok();
+    let client_version = get_client_version(context)
+        .map_err(|e| debug!(error = %e, "Failed to get client version"))
+        .ok();
+    let datadir_size = datadir_size(context)
+        .map_err(|e| debug!(error = %e, "Failed to get datadir size"))
+        .ok();
+
+    Ok(json!({
+        "services": services,
+        "sync": sync,
+        "client_version": client_version,
+        "datadir_size_bytes": datadir_size,
+    }))
+}
+
+/// Forward a `{ "method": ..., "params": [...] }` request to the Reth RPC, provided the method
+/// is in the configured allowlist, and return the raw JSON-RPC response
+pub fn rpc_passthrough(context: &RethContext, request: &str) -> Result<Value> {
+    let request: Value =
+        serde_json::from_str(request).map_err(Error::parse("Invalid RPC request"))?;
+
+    let method = request["method"]
+        .as_str()
+        .ok_or_else(|| Error::Other("RPC request is missing a method".to_string()))?;
+
+    if !context
+        .config
+        .rpc_allowlist
+        .iter()
+        .any(|allowed| allowed == method)
+    {
+        warn!(method = method, "Rejected RPC method not in allowlist");
+        return Err(Error::Other(format!(
+            "RPC method {} is not allowed",
+            method
+        )));
+    }
+
+    let params = match &request["params"] {
+        Value::Null => json!([]),
+        params => params.clone(),
+    };
+
+    rpc_request_raw(context, method, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RethConfig;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+
+    /// Serve `requests` JSON-RPC requests on an ephemeral port, answering each with the canned
+    /// result for its method. Returns the port.
+    fn mock_rpc_server(results: HashMap<&'static str, Value>, requests: usize) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((_, value)) = line
+                        .split_once(':')
+                        .filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let method = request["method"].as_str().unwrap();
+
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": results.get(method).cloned().unwrap_or(Value::Null),
+                })
+                .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+
+        port
+    }
+
+    fn mock_context(rpc_port: u16) -> RethContext {
+        RethContext::new(RethConfig {
+            // Commands run in the submodule directory, which doesn't exist in tests
+            submodule_path: PathBuf::from("."),
+            rpc_port,
+            ..RethConfig::default()
+        })
+    }
+
+    #[test]
+    fn sync_status_while_syncing() {
+        let port = mock_rpc_server(
+            HashMap::from([
+                (
+                    "eth_syncing",
+                    json!({
+                        "startingBlock": "0x0",
+                        "currentBlock": "0x10",
+                        "highestBlock": "0x20",
+                    }),
+                ),
+                ("eth_blockNumber", json!("0x10")),
+                ("net_peerCount", json!("0x5")),
+            ]),
+            3,
+        );
+
+        let status = get_sync_status(&mock_context(port)).unwrap();
+        assert_eq!(status, SyncStatus {
+            syncing: true,
+            current_block: Some(16),
+            highest_block: Some(32),
+            peer_count: Some(5),
+        });
+    }
+
+    #[test]
+    fn sync_status_when_synced() {
+        let port = mock_rpc_server(
+            HashMap::from([
+                ("eth_syncing", json!(false)),
+                ("eth_blockNumber", json!("0x1312d00")),
+                ("net_peerCount", json!("0x19")),
+            ]),
+            3,
+        );
+
+        let status = get_sync_status(&mock_context(port)).unwrap();
+        assert_eq!(status, SyncStatus {
+            syncing: false,
+            current_block: Some(20_000_000),
+            highest_block: None,
+            peer_count: Some(25),
+        });
+    }
+
+    #[test]
+    fn compose_ps_array_output() {
+        let output = r#"[{"Name":"reth-reth-1","Service":"reth","State":"running","Health":"healthy","Publishers":[{"URL":"0.0.0.0","TargetPort":8545,"PublishedPort":8545,"Protocol":"tcp"}]},{"Name":"reth-grafana-1","Service":"grafana","State":"exited","Health":"","Publishers":null}]"#;
+
+        let statuses = parse_compose_ps(output).unwrap();
+        assert_eq!(statuses, [
+            ServiceStatus {
+                name: "reth".to_string(),
+                container: "reth-reth-1".to_string(),
+                state: "running".to_string(),
+                health: Some("healthy".to_string()),
+                ports: vec![PortMapping {
+                    host_ip: "0.0.0.0".to_string(),
+                    host_port: 8545,
+                    container_port: 8545,
+                    protocol: "tcp".to_string(),
+                }],
+            },
+            ServiceStatus {
+                name: "grafana".to_string(),
+                container: "reth-grafana-1".to_string(),
+                state: "exited".to_string(),
+                health: None,
+                ports: Vec::new(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn compose_ps_line_output() {
+        let output = concat!(
+            r#"{"Name":"reth-reth-1","Service":"reth","State":"running","Health":"starting"}"#,
+            "\n\n",
+            r#"{"Name":"reth-prometheus-1","Service":"prometheus","State":"running","Health":""}"#,
+            "\n",
+        );
+
+        let statuses = parse_compose_ps(output).unwrap();
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].name, "reth");
+        assert_eq!(statuses[0].health.as_deref(), Some("starting"));
+        assert_eq!(statuses[1].name, "prometheus");
+        assert_eq!(statuses[1].health, None);
+        assert!(statuses.iter().all(ServiceStatus::is_running));
+    }
+
+    #[test]
+    fn compose_ps_empty_output() {
+        assert!(parse_compose_ps("").unwrap().is_empty());
+        assert!(parse_compose_ps("\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn compose_ps_invalid_output() {
+        assert!(matches!(
+            parse_compose_ps("not json"),
+            Err(Error::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn compose_ps_skips_unpublished_ports() {
+        let output = r#"{"Name":"reth-reth-1","Service":"reth","State":"running","Publishers":[{"URL":"","TargetPort":30303,"PublishedPort":0,"Protocol":"udp"},{"URL":"127.0.0.1","TargetPort":9001,"PublishedPort":9001,"Protocol":"tcp"}]}"#;
+
+        let statuses = parse_compose_ps(output).unwrap();
+        assert_eq!(statuses[0].ports, [PortMapping {
+            host_ip: "127.0.0.1".to_string(),
+            host_port: 9001,
+            container_port: 9001,
+            protocol: "tcp".to_string(),
+        }]);
+    }
+
+    fn service(name: &str, state: &str) -> ServiceStatus {
+        ServiceStatus {
+            name: name.to_string(),
+            container: format!("reth-{}-1", name),
+            state: state.to_string(),
+            health: None,
+            ports: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reth_running_only_counts_the_reth_service() {
+        assert!(!reth_is_running(&[]));
+
+        // After a restore the monitoring services keep running while reth is only created
+        assert!(!reth_is_running(&[
+            service("reth", "created"),
+            service("prometheus", "running"),
+            service("grafana", "running"),
+        ]));
+
+        // Compose v1 lists the containers kept by `stop`
+        assert!(!reth_is_running(&[
+            service("reth", "exited"),
+            service("grafana", "exited"),
+        ]));
+
+        assert!(reth_is_running(&[
+            service("reth", "running"),
+            service("grafana", "exited"),
+        ]));
+    }
+
+    #[test]
+    fn du_output() {
+        assert_eq!(parse_du_size("1234\t/data\n").unwrap(), 1234 * 1024);
+        assert_eq!(parse_du_size("0\t/data").unwrap(), 0);
+        assert!(parse_du_size("").is_err());
+        assert!(parse_du_size("du: /data: No such file or directory").is_err());
+    }
+
+    #[test]
+    fn inspect_output() {
+        let entry = json!({
+            "Name": "/reth-reth-1",
+            "Config": { "Labels": { "com.docker.compose.service": "reth" } },
+            "State": { "Status": "running", "Health": { "Status": "unhealthy" } },
+            "NetworkSettings": {
+                "Ports": {
+                    "8545/tcp": [{ "HostIp": "0.0.0.0", "HostPort": "8545" }],
+                    "30303/udp": null
+                }
+            }
+        });
+
+        assert_eq!(ServiceStatus::from_inspect_json(&entry), ServiceStatus {
+            name: "reth".to_string(),
+            container: "reth-reth-1".to_string(),
+            state: "running".to_string(),
+            health: Some("unhealthy".to_string()),
+            ports: vec![PortMapping {
+                host_ip: "0.0.0.0".to_string(),
+                host_port: 8545,
+                container_port: 8545,
+                protocol: "tcp".to_string(),
+            }],
+        });
+    }
+}